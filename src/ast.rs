@@ -1,111 +1,217 @@
-use syn::{FnArg, GenericArgument};
+use std::collections::HashMap;
 
-/// Query in fn handler(axum::Query(query): ...)
-pub fn pattern_type_without_path(arg: &FnArg) -> Option<String> {
-  dbg!(arg);
+use syn::{visit_mut::VisitMut, Attribute, FnArg, GenericArgument, Lit, Meta, NestedMeta};
+
+/// T in fn handler(Path(id): Path<T>) or fn handler(id: axum::extract::Path<T>)
+pub fn extractor_type<'a>(arg: &'a FnArg, extractor: &str) -> Option<&'a syn::Type> {
   match arg {
-    FnArg::Receiver(_) => todo!(),
-    FnArg::Typed(pat_type) => {
-      match &*pat_type.pat {
-        // fn handler(Query(query): Query<...>)
-        syn::Pat::TupleStruct(tuple_struct) => tuple_struct
+    FnArg::Receiver(_) => None,
+    FnArg::Typed(pat_type) => generic_type_argument(&pat_type.ty, extractor),
+  }
+}
+
+/// T in Name<T> or a::b::Name<T>
+pub fn generic_type_argument<'a>(ty: &'a syn::Type, name: &str) -> Option<&'a syn::Type> {
+  let segment = match ty {
+    syn::Type::Path(type_path) => type_path.path.segments.last()?,
+    _ => return None,
+  };
+
+  if segment.ident != name {
+    return None;
+  }
+
+  match &segment.arguments {
+    syn::PathArguments::AngleBracketed(args) => args.args.iter().find_map(|arg| match arg {
+      GenericArgument::Type(ty) => Some(ty),
+      _ => None,
+    }),
+    _ => None,
+  }
+}
+
+/// T in the wrappers that serde serializes as the value they hold: Box<T>, Arc<T>, Cow<'a, T>
+pub fn wrapped_type(ty: &syn::Type) -> Option<&syn::Type> {
+  [
+    "Box", "Rc", "Arc", "Cow", "Cell", "RefCell", "Mutex", "RwLock",
+  ]
+  .iter()
+  .find_map(|wrapper| generic_type_argument(ty, wrapper))
+}
+
+/// V in HashMap<K, V> or BTreeMap<K, V>
+pub fn map_value_type(ty: &syn::Type) -> Option<&syn::Type> {
+  let segment = match ty {
+    syn::Type::Path(type_path) => type_path.path.segments.last()?,
+    _ => return None,
+  };
+
+  if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+    return None;
+  }
+
+  match &segment.arguments {
+    syn::PathArguments::AngleBracketed(args) => args
+      .args
+      .iter()
+      .filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+      })
+      .nth(1),
+    _ => None,
+  }
+}
+
+/// [A, B] in Name<A, B>
+pub fn type_arguments(type_path: &syn::TypePath) -> Vec<&syn::Type> {
+  match type_path
+    .path
+    .segments
+    .last()
+    .map(|segment| &segment.arguments)
+  {
+    Some(syn::PathArguments::AngleBracketed(args)) => args
+      .args
+      .iter()
+      .filter_map(|arg| match arg {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+      })
+      .collect(),
+    _ => vec![],
+  }
+}
+
+/// Replaces the type parameters in `ty` with their arguments:
+/// Result<Json<T>, E> with T = User -> Result<Json<User>, E>
+pub fn substitute_type_parameters(ty: &mut syn::Type, arguments: &HashMap<String, syn::Type>) {
+  struct Substitution<'a>(&'a HashMap<String, syn::Type>);
+
+  impl<'a> VisitMut for Substitution<'a> {
+    fn visit_type_mut(&mut self, ty: &mut syn::Type) {
+      let argument = match ty {
+        syn::Type::Path(type_path) if type_path.qself.is_none() => type_path
           .path
-          .segments
-          .last()
-          .map(|segment| segment.ident.to_string()),
-        _ => todo!(),
+          .get_ident()
+          .and_then(|ident| self.0.get(&ident.to_string())),
+        _ => None,
+      };
+
+      match argument {
+        Some(argument) => *ty = argument.clone(),
+        None => syn::visit_mut::visit_type_mut(self, ty),
       }
     }
   }
+
+  Substitution(arguments).visit_type_mut(ty);
 }
 
-pub fn has_option_type(arg: &FnArg) -> bool {
-  inner_type_without_path(arg) == "Option"
+/// Expression returned by `func`: 10 in fn default_limit() -> u32 { 10 }
+pub fn tail_expr(block: &syn::Block) -> Option<&syn::Expr> {
+  match block.stmts.last()? {
+    syn::Stmt::Expr(expr) => Some(expr),
+    _ => None,
+  }
 }
 
-/// T in fn handler(Query(q): Query<T>)
-pub fn inner_type_without_path(arg: &FnArg) -> String {
-  match arg {
-    FnArg::Receiver(_) => todo!(),
-    FnArg::Typed(pat_type) => match &*pat_type.ty {
-      syn::Type::Array(_) => todo!(),
-      syn::Type::BareFn(_) => todo!(),
-      syn::Type::Group(_) => todo!(),
-      syn::Type::ImplTrait(_) => todo!(),
-      syn::Type::Infer(_) => todo!(),
-      syn::Type::Macro(_) => todo!(),
-      syn::Type::Never(_) => todo!(),
-      syn::Type::Paren(_) => todo!(),
-      syn::Type::Ptr(_) => todo!(),
-      syn::Type::Reference(_) => todo!(),
-      syn::Type::Slice(_) => todo!(),
-      syn::Type::TraitObject(_) => todo!(),
-      syn::Type::Tuple(_) => todo!(),
-      syn::Type::Verbatim(_) => todo!(),
-      syn::Type::Path(type_path) => {
-        let segment = type_path.path.segments.last().unwrap();
-
-        match segment.arguments {
-          syn::PathArguments::AngleBracketed(args) => {
-            let generic_arg = args.args.last().unwrap();
-            match generic_arg {
-              GenericArgument::Type(type_path) => match type_path {
-                syn::Type::Path(path) => match path.path.segments.last() {
-                  None => segment.ident.to_string(),
-                  Some(segment) => segment.ident.to_string(),
-                },
-                _ => todo!(),
-              },
-              _ => todo!(),
-            }
-          }
-          _ => todo!(),
-        }
+/// Value of a literal expression: 10, -1, 1.5, true, "a", String::from("a"), "a".to_string()
+pub fn literal_value(expr: &syn::Expr) -> Option<serde_json::Value> {
+  match expr {
+    syn::Expr::Lit(lit) => match &lit.lit {
+      Lit::Str(value) => Some(serde_json::Value::from(value.value())),
+      Lit::Int(value) => value
+        .base10_parse::<u64>()
+        .ok()
+        .map(serde_json::Value::from),
+      Lit::Float(value) => value
+        .base10_parse::<f64>()
+        .ok()
+        .map(serde_json::Value::from),
+      Lit::Bool(value) => Some(serde_json::Value::from(value.value)),
+      _ => None,
+    },
+    syn::Expr::Unary(unary) if matches!(unary.op, syn::UnOp::Neg(_)) => {
+      match literal_value(&unary.expr)? {
+        serde_json::Value::Number(number) => number
+          .as_i64()
+          .map(|number| serde_json::Value::from(-number))
+          .or_else(|| {
+            number
+              .as_f64()
+              .map(|number| serde_json::Value::from(-number))
+          }),
+        _ => None,
       }
-      _ => todo!(),
+    }
+    // String::from("a")
+    syn::Expr::Call(call) if call.args.len() == 1 => match &*call.func {
+      syn::Expr::Path(path) if path_segments(&path.path).ends_with(&[String::from("from")]) => {
+        literal_value(&call.args[0])
+      }
+      _ => None,
     },
+    // "a".to_string(), "a".to_owned(), "a".into()
+    syn::Expr::MethodCall(method_call)
+      if ["to_string", "to_owned", "into"].contains(&method_call.method.to_string().as_str()) =>
+    {
+      literal_value(&method_call.receiver)
+    }
+    syn::Expr::Paren(paren) => literal_value(&paren.expr),
+    syn::Expr::Block(block) => tail_expr(&block.block).and_then(literal_value),
+    _ => None,
   }
 }
 
-/// Query in fn handler(query: axum::Query<T>)
-pub fn arg_base_type_without_path(arg: &FnArg) -> String {
-  match arg {
-    FnArg::Receiver(_) => todo!(),
-    FnArg::Typed(pat_type) => match &*pat_type.ty {
-      syn::Type::Array(_) => todo!(),
-      syn::Type::BareFn(_) => todo!(),
-      syn::Type::Group(_) => todo!(),
-      syn::Type::ImplTrait(_) => todo!(),
-      syn::Type::Infer(_) => todo!(),
-      syn::Type::Macro(_) => todo!(),
-      syn::Type::Never(_) => todo!(),
-      syn::Type::Paren(_) => todo!(),
-      syn::Type::Ptr(_) => todo!(),
-      syn::Type::Reference(_) => todo!(),
-      syn::Type::Slice(_) => todo!(),
-      syn::Type::TraitObject(_) => todo!(),
-      syn::Type::Tuple(_) => todo!(),
-      syn::Type::Verbatim(_) => todo!(),
-      syn::Type::Path(type_path) => type_path.path.segments.last().unwrap().ident.to_string(),
-      _ => todo!(),
-    },
+/// u64 in u64 or std::primitive::u64
+pub fn type_name(ty: &syn::Type) -> Option<String> {
+  match ty {
+    syn::Type::Path(type_path) => type_path
+      .path
+      .segments
+      .last()
+      .map(|segment| segment.ident.to_string()),
+    _ => None,
   }
 }
 
-pub fn param_name(arg: &FnArg) -> String {
-  match arg {
-    FnArg::Receiver(_) => todo!(),
-    FnArg::Typed(pat_type) => {
-      match &*pat_type.pat {
-        // fn handler(Query(query): Query<...>)
-        syn::Pat::TupleStruct(tuple_struct) => match tuple_struct.pat.elems.last().unwrap() {
-          syn::Pat::Ident(ident) => ident.ident.to_string(),
-          _ => todo!(),
-        },
-        _ => todo!(),
-      }
-    }
-  }
+/// a::b::c -> [a, b, c]
+pub fn path_segments(path: &syn::Path) -> Vec<String> {
+  path
+    .segments
+    .iter()
+    .map(|segment| segment.ident.to_string())
+    .collect()
+}
+
+/// crate::a::Foo -> [crate, a]
+pub fn parent_module(qualified_name: &str) -> Vec<String> {
+  let mut segments: Vec<String> = qualified_name.split("::").map(String::from).collect();
+  segments.pop();
+  segments
+}
+
+/// "foo.rs" in #[path = "foo.rs"]
+pub fn path_attribute(attrs: &[Attribute]) -> Option<String> {
+  attrs.iter().find_map(|attr| match attr.parse_meta() {
+    Ok(Meta::NameValue(name_value)) if name_value.path.is_ident("path") => match name_value.lit {
+      Lit::Str(path) => Some(path.value()),
+      _ => None,
+    },
+    _ => None,
+  })
+}
+
+/// Returns true for items annotated with #[cfg(test)]
+pub fn is_cfg_test(attrs: &[Attribute]) -> bool {
+  attrs.iter().any(|attr| match attr.parse_meta() {
+    Ok(Meta::List(list)) if list.path.is_ident("cfg") => list
+      .nested
+      .iter()
+      .any(|nested| matches!(nested, NestedMeta::Meta(Meta::Path(path)) if path.is_ident("test"))),
+    _ => false,
+  })
 }
 
 /*pub fn get_function_parameters(func: &ItemFn) -> Vec<String> {
@@ -1,57 +1,105 @@
-use std::collections::HashMap;
-
-use crate::{ast, rust_type_to_openapi_type, Component, ParameterSchema};
-use syn::{token::Struct, FnArg, Type};
+/// A parameter of a route path.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PathParam {
+  pub name: String,
+  /// *rest matches the rest of the path.
+  pub wildcard: bool,
+}
 
-/// Returns true for params of the Query type.
+/// Converts an axum route to an OpenAPI path template.
 ///
-/// async fn handler(Query(params): Query<T>) {}
-pub fn is_query_param(arg: &FnArg) -> bool {
-  ast::pattern_type_without_path(arg)
-    .map(|ty| ty == "Query")
-    .unwrap_or(false)
-    || ast::arg_base_type_without_path(arg) == "Query"
+/// /users/:id/files/*path -> /users/{id}/files/{path}
+pub fn path_template(route: &str) -> (String, Vec<PathParam>) {
+  let mut params = vec![];
+
+  let template = route
+    .split('/')
+    .map(|segment| {
+      let (name, wildcard) = match (segment.strip_prefix(':'), segment.strip_prefix('*')) {
+        (Some(name), _) => (name, false),
+        (_, Some(name)) => (name, true),
+        _ => return segment.to_owned(),
+      };
+
+      params.push(PathParam {
+        name: name.to_owned(),
+        wildcard,
+      });
+
+      format!("{{{}}}", name)
+    })
+    .collect::<Vec<_>>()
+    .join("/");
+
+  (template, params)
 }
 
-pub fn arg_to_parameter_schema(
-  components: &HashMap<String, Vec<Component>>,
-  arg: &FnArg,
-) -> Vec<ParameterSchema> {
-  let typ = ast::inner_type_without_path(arg);
-  match components.get(&typ) {
-    None => vec![ParameterSchema::Enum {
-      r#type: String::from("string"),
-      default: String::from("unknown"),
-      r#enum: vec![String::from("unknown")],
-    }],
-    Some(components) => components
-      .iter()
-      .map(|component| ParameterSchema::Primitive {
-        r#type: match rust_type_to_openapi_type(&component.r#type) {
-          crate::Type::Primitive { r#type } => r#type,
-          _ => unreachable!(),
-        },
-      })
-      .collect(),
-  }
+/// Constants of http::StatusCode, their code and canonical reason phrase.
+const STATUS_CODES: [(&str, u16, &str); 41] = [
+  ("CONTINUE", 100, "Continue"),
+  ("SWITCHING_PROTOCOLS", 101, "Switching Protocols"),
+  ("OK", 200, "OK"),
+  ("CREATED", 201, "Created"),
+  ("ACCEPTED", 202, "Accepted"),
+  (
+    "NON_AUTHORITATIVE_INFORMATION",
+    203,
+    "Non Authoritative Information",
+  ),
+  ("NO_CONTENT", 204, "No Content"),
+  ("RESET_CONTENT", 205, "Reset Content"),
+  ("PARTIAL_CONTENT", 206, "Partial Content"),
+  ("MULTIPLE_CHOICES", 300, "Multiple Choices"),
+  ("MOVED_PERMANENTLY", 301, "Moved Permanently"),
+  ("FOUND", 302, "Found"),
+  ("SEE_OTHER", 303, "See Other"),
+  ("NOT_MODIFIED", 304, "Not Modified"),
+  ("TEMPORARY_REDIRECT", 307, "Temporary Redirect"),
+  ("PERMANENT_REDIRECT", 308, "Permanent Redirect"),
+  ("BAD_REQUEST", 400, "Bad Request"),
+  ("UNAUTHORIZED", 401, "Unauthorized"),
+  ("PAYMENT_REQUIRED", 402, "Payment Required"),
+  ("FORBIDDEN", 403, "Forbidden"),
+  ("NOT_FOUND", 404, "Not Found"),
+  ("METHOD_NOT_ALLOWED", 405, "Method Not Allowed"),
+  ("NOT_ACCEPTABLE", 406, "Not Acceptable"),
+  ("REQUEST_TIMEOUT", 408, "Request Timeout"),
+  ("CONFLICT", 409, "Conflict"),
+  ("GONE", 410, "Gone"),
+  ("LENGTH_REQUIRED", 411, "Length Required"),
+  ("PRECONDITION_FAILED", 412, "Precondition Failed"),
+  ("PAYLOAD_TOO_LARGE", 413, "Payload Too Large"),
+  ("UNSUPPORTED_MEDIA_TYPE", 415, "Unsupported Media Type"),
+  ("IM_A_TEAPOT", 418, "I'm a teapot"),
+  ("UNPROCESSABLE_ENTITY", 422, "Unprocessable Entity"),
+  ("LOCKED", 423, "Locked"),
+  ("PRECONDITION_REQUIRED", 428, "Precondition Required"),
+  ("TOO_MANY_REQUESTS", 429, "Too Many Requests"),
+  ("INTERNAL_SERVER_ERROR", 500, "Internal Server Error"),
+  ("NOT_IMPLEMENTED", 501, "Not Implemented"),
+  ("BAD_GATEWAY", 502, "Bad Gateway"),
+  ("SERVICE_UNAVAILABLE", 503, "Service Unavailable"),
+  ("GATEWAY_TIMEOUT", 504, "Gateway Timeout"),
+  (
+    "HTTP_VERSION_NOT_SUPPORTED",
+    505,
+    "HTTP Version Not Supported",
+  ),
+];
+
+/// Code of a http::StatusCode constant: CREATED -> 201
+pub fn status_code(constant: &str) -> Option<u16> {
+  STATUS_CODES
+    .iter()
+    .find(|(name, _, _)| *name == constant)
+    .map(|(_, code, _)| *code)
 }
 
-/*
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-enum ParameterSchema {
-  Enum {
-    r#type: integer,
-    default: nu,
-    r#enum: Vec<String>,
-  },
-  Array {
-    r#type: String,
-    items: Type,
-  },
-  Integer {
-    r#type: String,
-    format: String,
-  },
+/// Reason phrase of a status code: 404 -> Not Found
+pub fn reason_phrase(code: u16) -> &'static str {
+  STATUS_CODES
+    .iter()
+    .find(|(_, status_code, _)| *status_code == code)
+    .map(|(_, _, reason)| *reason)
+    .unwrap_or("Response")
 }
-*/
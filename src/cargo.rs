@@ -0,0 +1,288 @@
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+/// A bin or lib target of a package, each target gets its own document.
+#[derive(Debug)]
+pub struct Target {
+  /// Name of the package the target belongs to.
+  pub package: String,
+  /// Name of the target, bins are named after the package by default.
+  pub name: String,
+  pub kind: TargetKind,
+  /// Version of the package, if it has one.
+  pub version: Option<String>,
+  /// Root file of the target: src/main.rs, src/lib.rs, src/bin/foo.rs...
+  pub root: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetKind {
+  Lib,
+  Bin,
+}
+
+impl Target {
+  /// Name of the document generated for the target, libs are suffixed with -lib
+  /// since they usually have the same name as the package's main bin.
+  pub fn document_name(&self) -> String {
+    match self.kind {
+      TargetKind::Lib => format!("{}-lib", self.name),
+      TargetKind::Bin => self.name.clone(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+  package: Option<Package>,
+  lib: Option<TargetManifest>,
+  #[serde(default)]
+  bin: Vec<TargetManifest>,
+  workspace: Option<Workspace>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Package {
+  name: String,
+  /// Either a version or `{ workspace = true }`.
+  version: Option<toml::Value>,
+  autobins: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TargetManifest {
+  name: Option<String>,
+  path: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Workspace {
+  #[serde(default)]
+  members: Vec<String>,
+  #[serde(default)]
+  exclude: Vec<String>,
+  package: Option<WorkspacePackage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WorkspacePackage {
+  version: Option<String>,
+}
+
+/// Returns the targets of the package or workspace whose Cargo.toml is in `dir`.
+pub fn targets(dir: &Path) -> Result<Vec<Target>, Box<dyn std::error::Error>> {
+  let manifest = read_manifest(dir)?;
+
+  let workspace_version = manifest
+    .workspace
+    .as_ref()
+    .and_then(|workspace| workspace.package.as_ref())
+    .and_then(|package| package.version.clone());
+
+  let mut targets = package_targets(dir, &manifest, workspace_version.as_deref());
+
+  if let Some(workspace) = &manifest.workspace {
+    let excluded: Vec<PathBuf> = workspace
+      .exclude
+      .iter()
+      .map(|exclude| dir.join(exclude))
+      .collect();
+
+    for member in workspace.members.iter() {
+      for member_dir in expand_member(dir, member)? {
+        if excluded.contains(&member_dir) || !member_dir.join("Cargo.toml").is_file() {
+          continue;
+        }
+
+        let member_manifest = read_manifest(&member_dir)?;
+        targets.extend(package_targets(
+          &member_dir,
+          &member_manifest,
+          workspace_version.as_deref(),
+        ));
+      }
+    }
+  }
+
+  Ok(targets)
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, Box<dyn std::error::Error>> {
+  let path = dir.join("Cargo.toml");
+
+  let src = std::fs::read_to_string(&path)
+    .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+
+  toml::from_str(&src).map_err(|err| format!("unable to parse {}: {}", path.display(), err).into())
+}
+
+/// Returns the lib and bin targets declared in the manifest or found by cargo's target auto discovery.
+fn package_targets(
+  dir: &Path,
+  manifest: &Manifest,
+  workspace_version: Option<&str>,
+) -> Vec<Target> {
+  let package = match &manifest.package {
+    // Virtual manifest.
+    None => return vec![],
+    Some(package) => package,
+  };
+
+  let version = match &package.version {
+    Some(toml::Value::String(version)) => Some(version.clone()),
+    // version.workspace = true
+    Some(toml::Value::Table(_)) => workspace_version.map(String::from),
+    _ => None,
+  };
+
+  let target = |name: String, kind: TargetKind, root: PathBuf| Target {
+    package: package.name.clone(),
+    name,
+    kind,
+    version: version.clone(),
+    root,
+  };
+
+  let mut targets = vec![];
+
+  let lib_root = manifest
+    .lib
+    .as_ref()
+    .and_then(|lib| lib.path.clone())
+    .map(|path| dir.join(path))
+    .unwrap_or_else(|| dir.join("src/lib.rs"));
+
+  if lib_root.is_file() {
+    let name = manifest
+      .lib
+      .as_ref()
+      .and_then(|lib| lib.name.clone())
+      .unwrap_or_else(|| package.name.replace('-', "_"));
+
+    targets.push(target(name, TargetKind::Lib, lib_root));
+  }
+
+  for bin in manifest.bin.iter() {
+    let name = bin.name.clone().unwrap_or_else(|| package.name.clone());
+
+    let root = match &bin.path {
+      Some(path) => Some(dir.join(path)),
+      None => [
+        dir.join("src/bin").join(format!("{}.rs", name)),
+        dir.join("src/bin").join(&name).join("main.rs"),
+        dir.join("src/main.rs"),
+      ]
+      .into_iter()
+      .find(|path| path.is_file()),
+    };
+
+    match root {
+      None => tracing::warn!("unable to find the root file of bin {}", name),
+      Some(root) => targets.push(target(name, TargetKind::Bin, root)),
+    }
+  }
+
+  if package.autobins.unwrap_or(true) {
+    for (name, root) in discover_bins(dir, &package.name) {
+      let is_declared = targets.iter().any(|target| {
+        target.kind == TargetKind::Bin && (target.name == name || target.root == root)
+      });
+
+      if !is_declared {
+        targets.push(target(name, TargetKind::Bin, root));
+      }
+    }
+  }
+
+  targets
+}
+
+/// src/main.rs, src/bin/foo.rs and src/bin/foo/main.rs
+fn discover_bins(dir: &Path, package_name: &str) -> Vec<(String, PathBuf)> {
+  let mut bins = vec![];
+
+  let main = dir.join("src/main.rs");
+  if main.is_file() {
+    bins.push((package_name.to_owned(), main));
+  }
+
+  let mut entries: Vec<PathBuf> = match std::fs::read_dir(dir.join("src/bin")) {
+    Err(_) => return bins,
+    Ok(entries) => entries
+      .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+      .collect(),
+  };
+  entries.sort();
+
+  for path in entries {
+    let name = match path.file_stem().and_then(|stem| stem.to_str()) {
+      None => continue,
+      Some(name) => name.to_owned(),
+    };
+
+    if path.is_dir() && path.join("main.rs").is_file() {
+      bins.push((name, path.join("main.rs")));
+    } else if path
+      .extension()
+      .map(|extension| extension == "rs")
+      .unwrap_or(false)
+    {
+      bins.push((name, path));
+    }
+  }
+
+  bins
+}
+
+/// Expands the `*` wildcards of a workspace member: services/* -> [services/users, services/orders]
+fn expand_member(dir: &Path, member: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+  let mut paths = vec![dir.to_path_buf()];
+
+  for component in member.split('/') {
+    if !component.contains('*') {
+      paths = paths.into_iter().map(|path| path.join(component)).collect();
+      continue;
+    }
+
+    let mut expanded = vec![];
+
+    for path in paths.iter() {
+      let entries = match std::fs::read_dir(path) {
+        Err(_) => continue,
+        Ok(entries) => entries,
+      };
+
+      for entry in entries {
+        let entry = entry?;
+        let matches = entry
+          .file_name()
+          .to_str()
+          .map(|name| wildcard_match(component, name))
+          .unwrap_or(false);
+
+        if matches && entry.file_type()?.is_dir() {
+          expanded.push(entry.path());
+        }
+      }
+    }
+
+    expanded.sort();
+    paths = expanded;
+  }
+
+  Ok(paths)
+}
+
+/// Returns true when `name` matches `pattern`, where `*` matches any sequence of characters.
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+  match pattern.split_once('*') {
+    None => pattern == name,
+    Some((prefix, rest)) => {
+      name.starts_with(prefix)
+        && (0..=name.len() - prefix.len())
+          .filter(|i| name.is_char_boundary(prefix.len() + i))
+          .any(|i| wildcard_match(rest, &name[prefix.len() + i..]))
+    }
+  }
+}
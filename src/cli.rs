@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+
+/// Generates an OpenAPI document by reading the source code of an axum application.
+#[derive(Debug, Parser)]
+#[command(name = "swagger", about)]
+pub struct Args {
+  /// Rust source files, crate roots (directories containing `src/main.rs` or `src/lib.rs`)
+  /// or directories containing a `Cargo.toml`, in which case a document is generated for each
+  /// bin and lib target of the package or workspace.
+  #[arg(required = true)]
+  pub inputs: Vec<PathBuf>,
+
+  /// File to write the document to. The document is written to stdout when omitted.
+  /// When several documents are generated, the directory to write them to.
+  #[arg(short, long)]
+  pub output: Option<PathBuf>,
+
+  /// Only generate documents for the targets of these packages.
+  #[arg(short, long = "package")]
+  pub packages: Vec<String>,
+
+  /// Format of the generated document.
+  #[arg(short, long, value_enum, default_value_t = Format::Yaml)]
+  pub format: Format,
+
+  /// Overrides `info.title`.
+  #[arg(long)]
+  pub title: Option<String>,
+
+  /// Overrides `info.version`.
+  #[arg(long = "version")]
+  pub api_version: Option<String>,
+}
+
+impl Args {
+  /// Arguments used when generating a json document for `inputs` without any overrides.
+  #[cfg(test)]
+  pub fn new(inputs: Vec<PathBuf>) -> Self {
+    Self {
+      inputs,
+      output: None,
+      packages: vec![],
+      format: Format::Json,
+      title: None,
+      api_version: None,
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+  Yaml,
+  Json,
+}
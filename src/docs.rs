@@ -0,0 +1,330 @@
+use syn::{Attribute, Lit, Meta};
+
+/// The documentation of an item, written with /// comments.
+///
+/// Headings such as `# Tags: users` are directives, they fill the fields of the operation
+/// instead of being part of the description:
+///
+/// /// Get a user.
+/// ///
+/// /// # Tags: users
+/// /// # OperationId: getUser
+/// /// # Deprecated
+/// /// # Responses
+/// /// - 404: User not found
+#[derive(Debug, Default, PartialEq)]
+pub struct Docs {
+  /// The first paragraph.
+  pub summary: Option<String>,
+  /// The paragraphs after the first one, Markdown is kept as is.
+  pub description: Option<String>,
+  /// # Tags: users, admin
+  pub tags: Vec<String>,
+  /// Status code and description of each item of # Responses: [(404, User not found)]
+  pub responses: Vec<(String, String)>,
+  /// # Deprecated
+  pub deprecated: bool,
+  /// # OperationId: getUser
+  pub operation_id: Option<String>,
+  /// ```json blocks under # Example, the examples of a schema.
+  pub examples: Vec<Example>,
+  /// ```json blocks under # Request example
+  pub request_examples: Vec<Example>,
+  /// ```json blocks under # Response example
+  pub response_examples: Vec<Example>,
+}
+
+/// A ```json block of an example section.
+#[derive(Debug, PartialEq)]
+pub struct Example {
+  /// The line of text before the block: `Admin user:` -> Admin user
+  pub name: Option<String>,
+  pub value: serde_json::Value,
+}
+
+/// A `# Name: value` heading and the lines until the next heading.
+struct Section<'a> {
+  /// `Name: value`, None for the lines before the first heading.
+  heading: Option<&'a str>,
+  /// `# Name: value`
+  heading_line: &'a str,
+  lines: Vec<&'a str>,
+}
+
+/// Parses the /// comments (#[doc = "..."] attributes) of the item `item_name`.
+pub fn parse(item_name: &str, attrs: &[Attribute]) -> Docs {
+  let lines = doc_lines(attrs);
+
+  let mut docs = Docs::default();
+  let mut body = vec![];
+
+  for section in sections(&lines) {
+    match section.heading {
+      None => body.extend(section.lines),
+      Some(heading) => {
+        if !docs.apply_directive(item_name, heading, &section.lines) {
+          tracing::warn!(
+            "unknown directive `# {}` in the documentation of {}",
+            heading,
+            item_name
+          );
+
+          // The previous section may already end with a blank line.
+          if body.last().is_some_and(|line| !line.trim().is_empty()) {
+            body.push("");
+          }
+          body.push(section.heading_line);
+          body.extend(section.lines);
+        }
+      }
+    }
+  }
+
+  let mut paragraphs = body
+    .iter()
+    .skip_while(|line| line.trim().is_empty())
+    .peekable();
+
+  let mut summary = vec![];
+  while let Some(line) = paragraphs.next_if(|line| !line.trim().is_empty()) {
+    summary.push(line.trim());
+  }
+
+  let description: Vec<&str> = paragraphs
+    .skip_while(|line| line.trim().is_empty())
+    .copied()
+    .collect();
+
+  docs.summary = non_empty(summary.join(" "));
+  docs.description = non_empty(description.join("\n").trim_end().to_owned());
+
+  docs
+}
+
+impl Docs {
+  /// The summary and description, used as the description of schemas.
+  pub fn text(&self) -> Option<String> {
+    match (&self.summary, &self.description) {
+      (Some(summary), Some(description)) => Some(format!("{}\n\n{}", summary, description)),
+      (summary, description) => summary.clone().or_else(|| description.clone()),
+    }
+  }
+
+  /// Fills the field of the directive `heading`, returns false for unknown directives.
+  fn apply_directive(&mut self, item_name: &str, heading: &str, lines: &[&str]) -> bool {
+    let (name, value) = match heading.split_once(':') {
+      None => (heading, ""),
+      Some((name, value)) => (name, value.trim()),
+    };
+
+    // OperationId, Operation Id and operation-id are the same directive.
+    let name: String = name
+      .chars()
+      .filter(|c| c.is_alphanumeric())
+      .flat_map(char::to_lowercase)
+      .collect();
+
+    match name.as_str() {
+      "tags" | "tag" => {
+        // # Tags: users, admin or a list of tags.
+        let list_items = lines.iter().filter_map(|line| list_item(line));
+
+        self.tags.extend(
+          value
+            .split(',')
+            .chain(list_items)
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from),
+        );
+      }
+      "responses" => {
+        for line in lines.iter().filter(|line| !line.trim().is_empty()) {
+          match parse_response(line) {
+            Some(response) => self.responses.push(response),
+            None => tracing::warn!(
+              "invalid response `{}` in the documentation of {}, expected `- 404: description`",
+              line.trim(),
+              item_name
+            ),
+          }
+        }
+      }
+      "deprecated" => self.deprecated = true,
+      "example" | "examples" => self.examples.extend(json_examples(item_name, lines)),
+      "requestexample" | "requestexamples" => self
+        .request_examples
+        .extend(json_examples(item_name, lines)),
+      "responseexample" | "responseexamples" => self
+        .response_examples
+        .extend(json_examples(item_name, lines)),
+      "operationid" => match value {
+        "" => tracing::warn!("missing operation id in the documentation of {}", item_name),
+        value => self.operation_id = Some(value.to_owned()),
+      },
+      _ => return false,
+    }
+
+    true
+  }
+}
+
+/// Splits the lines at `# Heading` lines, headings in ``` code blocks are ignored.
+fn sections(lines: &[String]) -> Vec<Section<'_>> {
+  let mut sections = vec![Section {
+    heading: None,
+    heading_line: "",
+    lines: vec![],
+  }];
+
+  let mut in_code_block = false;
+
+  for line in lines.iter() {
+    if line.trim_start().starts_with("```") {
+      in_code_block = !in_code_block;
+    }
+
+    match line.strip_prefix("# ") {
+      Some(heading) if !in_code_block => sections.push(Section {
+        heading: Some(heading.trim()),
+        heading_line: line,
+        lines: vec![],
+      }),
+      _ => sections.last_mut().unwrap().lines.push(line),
+    }
+  }
+
+  sections
+}
+
+/// Parses the ```json blocks of an example section, blocks that aren't valid JSON are reported and skipped.
+fn json_examples(item_name: &str, lines: &[&str]) -> Vec<Example> {
+  let mut examples = vec![];
+
+  let mut name = None;
+  // The language and lines of the code block being read.
+  let mut code_block: Option<(&str, Vec<&str>)> = None;
+
+  for line in lines.iter() {
+    let fence = line.trim_start().strip_prefix("```").map(str::trim);
+
+    match (&mut code_block, fence) {
+      (None, Some(language)) => code_block = Some((language, vec![])),
+      (None, None) if !line.trim().is_empty() => {
+        name = Some(line.trim().trim_end_matches(':').to_owned());
+      }
+      (None, None) => {}
+      (Some((language, block_lines)), Some(_)) => {
+        if *language != "json" {
+          tracing::warn!(
+            "```{} block in an example of {} was skipped, examples must be ```json blocks",
+            language,
+            item_name
+          );
+        } else {
+          match serde_json::from_str(&block_lines.join("\n")) {
+            Ok(value) => examples.push(Example {
+              name: name.take(),
+              value,
+            }),
+            Err(err) => tracing::warn!(
+              "invalid JSON example in the documentation of {}: {}",
+              item_name,
+              err
+            ),
+          }
+        }
+
+        code_block = None;
+      }
+      (Some((_, block_lines)), None) => block_lines.push(line),
+    }
+  }
+
+  if code_block.is_some() {
+    tracing::warn!("unclosed ``` block in the documentation of {}", item_name);
+  }
+
+  examples
+}
+
+/// users in `- users` or `* users`
+fn list_item(line: &str) -> Option<&str> {
+  let line = line.trim_start();
+
+  line.strip_prefix("- ").or_else(|| line.strip_prefix("* "))
+}
+
+/// - 404: User not found -> (404, User not found)
+///
+/// The status code may also be a range (4XX) or `default`.
+fn parse_response(line: &str) -> Option<(String, String)> {
+  let line = list_item(line).unwrap_or(line);
+  let (status, description) = line.split_once(':')?;
+  let status = status.trim();
+
+  let is_status_code = status.len() == 3
+    && status.starts_with(|c: char| ('1'..='5').contains(&c))
+    && (status[1..].chars().all(|c| c.is_ascii_digit()) || status[1..].eq_ignore_ascii_case("xx"));
+
+  if !is_status_code && status != "default" {
+    return None;
+  }
+
+  // 4xx -> 4XX
+  let status = if status == "default" {
+    status.to_owned()
+  } else {
+    status.to_uppercase()
+  };
+
+  Some((status, description.trim().to_owned()))
+}
+
+/// All the /// comments of an item, used as the description of schemas and properties.
+pub fn description(attrs: &[Attribute]) -> Option<String> {
+  let lines = doc_lines(attrs);
+
+  non_empty(lines.join("\n").trim().to_owned())
+}
+
+/// Lines of the #[doc] attributes, without the space that follows /// and the * of /** */ comments
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+  attrs
+    .iter()
+    .filter(|attr| attr.path.is_ident("doc"))
+    .filter_map(|attr| match attr.parse_meta() {
+      Ok(Meta::NameValue(name_value)) => match name_value.lit {
+        Lit::Str(doc) => Some(doc.value()),
+        _ => None,
+      },
+      _ => None,
+    })
+    .flat_map(|doc| {
+      // /** */ comments are a single attribute, their lines usually start with *
+      let is_block = doc.contains('\n');
+
+      doc
+        .split('\n')
+        .map(|line| {
+          let line = if is_block {
+            let trimmed = line.trim_start();
+            trimmed.strip_prefix('*').unwrap_or(trimmed)
+          } else {
+            line
+          };
+
+          line.strip_prefix(' ').unwrap_or(line).to_owned()
+        })
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+fn non_empty(text: String) -> Option<String> {
+  if text.is_empty() {
+    None
+  } else {
+    Some(text)
+  }
+}
@@ -1,13 +1,30 @@
-use lazy_static::lazy_static;
-use regex::Regex;
+use clap::Parser;
 use serde::Serialize;
 
-use std::{collections::HashMap, fs::File, io::Read};
-use syn::{Expr, ExprMethodCall, Item, ItemFn, ItemStruct, Stmt};
+use router::RouterEntry;
+use scope::SymbolTable;
+use serde_attr::{DefaultValue, EnumRepresentation};
+use std::{
+  collections::{BTreeMap, HashMap, HashSet},
+  io::Write,
+  path::{Path as FsPath, PathBuf},
+  process::ExitCode,
+};
+use syn::{
+  ext::IdentExt, ImplItem, Item, ItemEnum, ItemFn, ItemImpl, ItemMod, ItemStruct, ItemType,
+};
+use tracing_subscriber::EnvFilter;
 
 mod ast;
 mod axum;
+mod cargo;
+mod cli;
+mod docs;
 mod item;
+mod response;
+mod router;
+mod scope;
+mod serde_attr;
 
 #[cfg(test)]
 mod tests;
@@ -20,295 +37,1805 @@ pub struct Controller {
   pub response_body: Option<String>,
 }
 
-#[derive(Debug)]
+/// The handlers of a path, keyed by lowercase HTTP method.
+///
+/// .route("/users", get(list).post(create)) -> { get: list, post: create }
+#[derive(Debug, Clone, Default)]
 pub struct RouteHandler {
-  pub method: String,
-  pub handler_name: String,
+  pub methods: BTreeMap<String, Handler>,
 }
 
-#[derive(Debug)]
-struct Component {
-  pub r#type: String,
-  pub required: bool,
-  pub field_name: Option<String>,
+#[derive(Debug, Clone)]
+pub struct Handler {
+  /// Path to the handler as written in the source code: handlers::users::create
+  pub handler_path: Vec<String>,
+  /// Module the route was registered in, the handler path is resolved from it.
+  pub module: Vec<String>,
 }
 
-fn main() {
-  run("/home/bruno/dev/rust/swagger/src/test.rs").unwrap();
-}
+fn main() -> ExitCode {
+  tracing_subscriber::fmt()
+    // Warnings about the source code are shown unless RUST_LOG says otherwise.
+    .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+    .with_writer(std::io::stderr)
+    .init();
+
+  let args = cli::Args::parse();
 
-lazy_static! {
-  static ref OPTION_REGEX: Regex = Regex::new(r#"Option<(.+)>"#).unwrap();
-  static ref SERDE_YAML_ENUM_TAG_REGEX: Regex = Regex::new(r#"!.+"#).unwrap();
+  let result = run(&args).and_then(|documents| write_documents(&args, documents));
+
+  match result {
+    Ok(()) => ExitCode::SUCCESS,
+    Err(err) => {
+      eprintln!("error: {}", err);
+      ExitCode::FAILURE
+    }
+  }
 }
 
 #[derive(Debug)]
 struct AstTraverser {
   structs: HashMap<String, ItemStruct>,
-  routes: HashMap<String, RouteHandler>,
-  used_types: Vec<String>,
+  enums: HashMap<String, ItemEnum>,
+  /// `type` aliases, keyed by qualified name.
+  type_aliases: HashMap<String, ItemType>,
+  /// Impl blocks and the module they were declared in.
+  impls: Vec<(Vec<String>, ItemImpl)>,
+  /// What each function does to the Router it builds, keyed by qualified function name.
+  routers: HashMap<String, Vec<RouterEntry>>,
+  used_types: Vec<UsedType>,
   fn_declarations: HashMap<String, ItemFn>,
-  components: HashMap<String, Vec<Component>>,
+  /// Schemas of the components, keyed by component name.
+  components: HashMap<String, Schema>,
+  symbols: SymbolTable,
+}
+
+/// A struct or enum of the project used in the document.
+///
+/// Each use of a generic type with other type arguments is a component: Page<User> -> PageUser
+#[derive(Debug, Clone)]
+struct UsedType {
+  qualified_name: String,
+  component_name: String,
+  arguments: Vec<TypeArgument>,
+}
+
+/// The type a generic parameter stands for.
+#[derive(Debug, Clone)]
+struct TypeArgument {
+  /// User in Page<User>, part of the name of the component.
+  name: String,
+  schema: Schema,
+}
+
+/// Type arguments of the generic parameters in scope, keyed by parameter name: T -> User
+type Generics = HashMap<String, TypeArgument>;
+
+/// The module being traversed.
+#[derive(Debug)]
+struct ModuleContext {
+  /// crate::handlers::users -> [crate, handlers, users]
+  path: Vec<String>,
+  /// Directory of the file that contains the module.
+  file_dir: PathBuf,
+  /// Directory that contains the files of `mod foo;` declarations.
+  module_dir: PathBuf,
+  /// Whether the module is a `mod foo { ... }` block.
+  inline: bool,
+}
+
+impl ModuleContext {
+  /// `#[path]` is relative to the directory of the file, unless it is inside of a `mod foo { ... }` block.
+  fn path_attribute_base(&self) -> &FsPath {
+    if self.inline {
+      &self.module_dir
+    } else {
+      &self.file_dir
+    }
+  }
+
+  /// Foo -> crate::module::Foo
+  fn qualified_name(&self, name: &str) -> String {
+    format!("{}::{}", self.path.join("::"), name)
+  }
 }
 
 impl AstTraverser {
   pub fn new() -> Self {
     Self {
       structs: HashMap::new(),
+      enums: HashMap::new(),
+      type_aliases: HashMap::new(),
+      impls: Vec::new(),
       fn_declarations: HashMap::new(),
-      routes: HashMap::new(),
+      routers: HashMap::new(),
       used_types: Vec::new(),
       components: HashMap::new(),
+      symbols: SymbolTable::new(),
     }
   }
 
-  pub fn traverse(&mut self, syntax: syn::File) {
-    for item in syntax.items.into_iter() {
+  /// Traverses the crate whose root is `path`, following every `mod` declaration.
+  pub fn traverse_crate(&mut self, path: &FsPath) -> Result<(), Box<dyn std::error::Error>> {
+    self.traverse_file(path, vec![String::from("crate")], true)
+  }
+
+  /// Traverses the module defined in `path`.
+  ///
+  /// `owns_dir` is true for crate roots, `mod.rs` files and files loaded with `#[path]`,
+  /// their submodules are found in the directory that contains them. The submodules of
+  /// other files such as `foo.rs` are found in `foo/`.
+  fn traverse_file(
+    &mut self,
+    path: &FsPath,
+    module_path: Vec<String>,
+    owns_dir: bool,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    let src = std::fs::read_to_string(path)
+      .map_err(|err| format!("unable to read {}: {}", path.display(), err))?;
+
+    let syntax = syn::parse_file(&src)
+      .map_err(|err| format!("unable to parse {}: {}", path.display(), err))?;
+
+    let file_dir = path.parent().map(FsPath::to_path_buf).unwrap_or_default();
+
+    let module_dir = if owns_dir {
+      file_dir.clone()
+    } else {
+      file_dir.join(path.file_stem().unwrap_or_default())
+    };
+
+    self.traverse_items(
+      syntax.items,
+      &ModuleContext {
+        path: module_path,
+        file_dir,
+        module_dir,
+        inline: false,
+      },
+    )
+  }
+
+  fn traverse_items(
+    &mut self,
+    items: Vec<Item>,
+    module: &ModuleContext,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    for item in items.into_iter() {
       match item {
+        Item::Mod(item_mod) => self.traverse_mod(item_mod, module)?,
+        Item::Use(item_use) => self.symbols.add_use(&module.path, &item_use.tree),
         Item::Struct(struct_) => {
-          self.structs.insert(struct_.ident.to_string(), struct_);
+          let struct_name = struct_.ident.to_string();
+          self.symbols.add_item(&module.path, struct_name.clone());
+          self
+            .structs
+            .insert(module.qualified_name(&struct_name), struct_);
+        }
+        Item::Enum(enum_) => {
+          let enum_name = enum_.ident.to_string();
+          self.symbols.add_item(&module.path, enum_name.clone());
+          self.enums.insert(module.qualified_name(&enum_name), enum_);
         }
+        Item::Type(alias) => {
+          let alias_name = alias.ident.to_string();
+          self.symbols.add_item(&module.path, alias_name.clone());
+          self
+            .type_aliases
+            .insert(module.qualified_name(&alias_name), alias);
+        }
+        Item::Impl(impl_) => self.impls.push((module.path.clone(), impl_)),
         Item::Fn(func) => {
           let func_name = func.sig.ident.to_string();
-          if func_name != "main" {
-            self.fn_declarations.insert(func_name, func.clone());
+          self.symbols.add_item(&module.path, func_name.clone());
+          self
+            .fn_declarations
+            .insert(module.qualified_name(&func_name), func.clone());
+
+          let entries = router::router_entries(&func.block, &module.path);
+          if !entries.is_empty() {
+            self
+              .routers
+              .insert(module.qualified_name(&func_name), entries);
           }
+        }
+        _ => {}
+      }
+    }
 
-          for stmt in func.block.stmts.into_iter() {
-            match stmt {
-              Stmt::Item(_) | Stmt::Expr(_) => continue,
-              Stmt::Semi(expr, _tokens) => match expr {
-                Expr::MethodCall(method_call) => self.handle_method_call(method_call),
-                _ => continue,
-              },
-              Stmt::Local(local_stmt) => {
-                if let Some(init) = local_stmt.init {
-                  if let Expr::MethodCall(method_call) = *init.1 {
-                    self.handle_method_call(method_call)
-                  }
+    Ok(())
+  }
+
+  /// Traverses `mod foo { ... }` blocks and the files of `mod foo;` declarations.
+  fn traverse_mod(
+    &mut self,
+    item_mod: ItemMod,
+    module: &ModuleContext,
+  ) -> Result<(), Box<dyn std::error::Error>> {
+    // Test modules do not register routes the application serves.
+    if ast::is_cfg_test(&item_mod.attrs) {
+      return Ok(());
+    }
+
+    let name = item_mod.ident.to_string();
+    let path_attribute = ast::path_attribute(&item_mod.attrs);
+
+    self.symbols.add_item(&module.path, name.clone());
+    let mut module_path = module.path.clone();
+    module_path.push(name.clone());
+
+    match item_mod.content {
+      Some((_, items)) => {
+        let module_dir = match path_attribute {
+          None => module.module_dir.join(&name),
+          Some(path) => module.path_attribute_base().join(path),
+        };
+
+        self.traverse_items(
+          items,
+          &ModuleContext {
+            path: module_path,
+            file_dir: module.file_dir.clone(),
+            module_dir,
+            inline: true,
+          },
+        )
+      }
+      None => {
+        let candidates = match path_attribute {
+          None => vec![
+            (module.module_dir.join(format!("{}.rs", name)), false),
+            (module.module_dir.join(&name).join("mod.rs"), true),
+          ],
+          Some(path) => vec![(module.path_attribute_base().join(path), true)],
+        };
+
+        match candidates.into_iter().find(|(path, _)| path.is_file()) {
+          None => {
+            tracing::warn!(
+              "unable to find the file of module {} in {}",
+              name,
+              module.module_dir.display()
+            );
+            Ok(())
+          }
+          Some((path, owns_dir)) => self.traverse_file(&path, module_path, owns_dir),
+        }
+      }
+    }
+  }
+
+  /// Returns the routes the application serves.
+  ///
+  /// Functions whose Router isn't nested or merged into another Router are the roots,
+  /// the routes of the Routers they nest are added with the nesting prefix.
+  ///
+  /// Routes registered more than once for the same path are merged into a single RouteHandler.
+  fn collect_routes(&self) -> BTreeMap<String, RouteHandler> {
+    let nested_routers: HashSet<String> = self
+      .routers
+      .values()
+      .flatten()
+      .filter_map(|entry| match entry {
+        RouterEntry::Nest { router, module, .. } => self.symbols.resolve(module, router),
+        RouterEntry::Route { .. } => None,
+      })
+      .collect();
+
+    let mut routes = BTreeMap::new();
+
+    for func_name in self.routers.keys() {
+      if !nested_routers.contains(func_name) {
+        self.collect_router_routes(func_name, "", &mut vec![], &mut routes);
+      }
+    }
+
+    routes
+  }
+
+  fn collect_router_routes(
+    &self,
+    func_name: &str,
+    prefix: &str,
+    stack: &mut Vec<String>,
+    routes: &mut BTreeMap<String, RouteHandler>,
+  ) {
+    // A Router that nests itself.
+    if stack.iter().any(|name| name == func_name) {
+      return;
+    }
+
+    let entries = match self.routers.get(func_name) {
+      None => return,
+      Some(entries) => entries,
+    };
+
+    stack.push(func_name.to_owned());
+
+    for entry in entries.iter() {
+      match entry {
+        RouterEntry::Route { path, handler } => {
+          routes
+            .entry(router::join_paths(prefix, path))
+            .or_default()
+            .methods
+            .extend(handler.methods.clone());
+        }
+        RouterEntry::Nest {
+          prefix: nest_prefix,
+          router,
+          module,
+        } => match self.symbols.resolve(module, router) {
+          None => tracing::debug!("unable to find nested router {}", router.join("::")),
+          Some(nested_func_name) => self.collect_router_routes(
+            &nested_func_name,
+            &router::join_paths(prefix, nest_prefix),
+            stack,
+            routes,
+          ),
+        },
+      }
+    }
+
+    stack.pop();
+  }
+
+  /// Records the project types used as request and response bodies: T in Json<T>
+  /// and the body of the error responses of handlers that return Result<T, AppError>.
+  pub fn collect_used_types(&mut self) {
+    let mut used_types = vec![];
+    let mut error_objects = vec![];
+
+    for route_handler in self.collect_routes().values() {
+      for handler in route_handler.methods.values() {
+        let (handler_name, controller_fn) = match self.resolve_handler(handler) {
+          None => continue,
+          Some(handler) => handler,
+        };
+
+        let module = ast::parent_module(&handler_name);
+        let return_type = self.handler_return_type(&module, controller_fn);
+        let output = return_type.as_ref().map(|(output, _)| output);
+
+        let response_types = response::handler_responses(output, &controller_fn.block)
+          .into_iter()
+          .filter_map(|response| match response.body {
+            response::Body::Json(body_type) => Some(body_type),
+            _ => None,
+          });
+
+        let body_types = request_body_type(controller_fn)
+          .map(|(body_type, _)| body_type)
+          .into_iter()
+          .chain(response_types);
+
+        for body_type in body_types {
+          self.type_schema(&module, &Generics::new(), body_type, &mut used_types);
+        }
+
+        if let Some((error_name, impl_module, into_response)) = return_type
+          .as_ref()
+          .and_then(|(output, output_module)| self.error_impl(output_module, output))
+        {
+          for error_response in response::into_response_responses(into_response) {
+            match error_response.body {
+              response::Body::JsonStruct(path) => {
+                if let Some(qualified_name) = self.json_struct_name(impl_module, path) {
+                  used_types.push(UsedType {
+                    component_name: self.component_name(&qualified_name),
+                    qualified_name,
+                    arguments: vec![],
+                  });
                 }
               }
+              response::Body::JsonObject(keys) => {
+                error_objects.push((self.component_name(&error_name), keys));
+              }
+              _ => {}
             }
           }
         }
-        _ => {}
       }
     }
+
+    for used_type in used_types {
+      if !self
+        .used_types
+        .iter()
+        .any(|known| known.component_name == used_type.component_name)
+      {
+        self.used_types.push(used_type);
+      }
+    }
+
+    // Bodies built with json!({ .. }) get a component named after the error type.
+    for (component_name, keys) in error_objects {
+      self.components.entry(component_name).or_insert_with(|| {
+        let mut schema = Schema::object();
+
+        for (key, rust_type) in keys {
+          let property = primitive_schema(&rust_type).unwrap_or_default();
+          schema.add_property(key, property, true);
+        }
+
+        schema
+      });
+    }
+  }
+
+  /// Builds the components of the used types and of the types they refer to.
+  pub fn build_type_components(&mut self) {
+    let mut pending = self.used_types.clone();
+
+    while let Some(used_type) = pending.pop() {
+      let UsedType {
+        qualified_name,
+        component_name,
+        arguments,
+      } = used_type;
+
+      if self.components.contains_key(&component_name) {
+        continue;
+      }
+
+      let (mut schema, attrs) = if let Some(struct_) = self.structs.get(&qualified_name) {
+        let generics = bind_type_parameters(&struct_.generics, arguments);
+
+        (
+          self.struct_schema(
+            &qualified_name,
+            struct_,
+            &generics,
+            &mut vec![qualified_name.clone()],
+            &mut pending,
+          ),
+          &struct_.attrs,
+        )
+      } else if let Some(enum_) = self.enums.get(&qualified_name) {
+        let generics = bind_type_parameters(&enum_.generics, arguments);
+
+        (
+          self.enum_schema(&qualified_name, enum_, &generics, &mut pending),
+          &enum_.attrs,
+        )
+      } else {
+        tracing::debug!("used type {} isn't declared in the project", qualified_name);
+        continue;
+      };
+
+      let docs = docs::parse(&qualified_name, attrs);
+      schema.description = docs.text();
+      schema.example = docs
+        .examples
+        .into_iter()
+        .next()
+        .map(|example| example.value);
+
+      self.components.insert(component_name, schema);
+    }
+  }
+
+  /// Name of the component generated for the struct or enum `qualified_name`.
+  ///
+  /// Types are named after their identifier, unless another type in the crate has
+  /// the same identifier, then the module path tells them apart: handlers.users.CreateRequest
+  fn component_name(&self, qualified_name: &str) -> String {
+    let ident = qualified_name.rsplit("::").next().unwrap_or(qualified_name);
+
+    let is_ambiguous = self
+      .structs
+      .keys()
+      .chain(self.enums.keys())
+      .filter(|name| name.rsplit("::").next() == Some(ident))
+      .count()
+      > 1;
+
+    if is_ambiguous {
+      qualified_name
+        .trim_start_matches("crate::")
+        .replace("::", ".")
+    } else {
+      ident.to_owned()
+    }
+  }
+
+  /// Schema of the type `ty` used in `module`.
+  ///
+  /// Structs and enums of the project are referred to with a $ref, they are added to `used_types`
+  /// so their component gets built. The generic parameters are replaced by their `generics` argument.
+  fn type_schema(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    ty: &syn::Type,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    if let Some(argument) = type_parameter(ty).and_then(|param| generics.get(&param)) {
+      return argument.schema.clone();
+    }
+
+    // Whether the value is required is up to the field.
+    if let Some(inner_type) = ast::generic_type_argument(ty, "Option") {
+      return self.type_schema(module, generics, inner_type, used_types);
+    }
+
+    if let Some(inner_type) = ast::wrapped_type(ty) {
+      return self.type_schema(module, generics, inner_type, used_types);
+    }
+
+    match ty {
+      // &T, &[T]
+      syn::Type::Reference(reference) => {
+        return self.type_schema(module, generics, &reference.elem, used_types);
+      }
+      syn::Type::Slice(slice) => {
+        return Schema::array(self.type_schema(module, generics, &slice.elem, used_types));
+      }
+      syn::Type::Tuple(tuple) => {
+        return self.tuple_schema(module, generics, tuple.elems.iter(), used_types);
+      }
+      // [T; 3] has exactly 3 items.
+      syn::Type::Array(array) => {
+        let len = match &array.len {
+          syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(len),
+            ..
+          }) => len.base10_parse::<u64>().ok(),
+          _ => None,
+        };
+
+        return Schema {
+          min_items: len,
+          max_items: len,
+          ..Schema::array(self.type_schema(module, generics, &array.elem, used_types))
+        };
+      }
+      _ => {}
+    }
+
+    // HashMap<String, V> -> {"type": "object", "additionalProperties": V}
+    if let Some(value_type) = ast::map_value_type(ty) {
+      let value_schema = self.type_schema(module, generics, value_type, used_types);
+
+      return Schema {
+        additional_properties: Some(AdditionalProperties::Schema(Box::new(value_schema))),
+        ..Schema::object()
+      };
+    }
+
+    let collection = ast::type_name(ty).and_then(|type_name| match type_name.as_str() {
+      "Vec" | "VecDeque" | "LinkedList" | "HashSet" | "BTreeSet" => {
+        let item_type = ast::generic_type_argument(ty, &type_name)?;
+        Some((item_type, type_name.ends_with("Set")))
+      }
+      _ => None,
+    });
+
+    if let Some((item_type, unique_items)) = collection {
+      return Schema {
+        unique_items,
+        ..Schema::array(self.type_schema(module, generics, item_type, used_types))
+      };
+    }
+
+    if let syn::Type::Path(type_path) = ty {
+      // type UserId = Uuid;
+      if let Some((qualified_name, alias)) = self.resolve_alias(module, type_path) {
+        let arguments = self.type_arguments(module, generics, type_path, used_types);
+        let alias_generics = bind_type_parameters(&alias.generics, arguments);
+
+        return self.type_schema(
+          &ast::parent_module(&qualified_name),
+          &alias_generics,
+          &alias.ty,
+          used_types,
+        );
+      }
+
+      if let Some(qualified_name) = self.resolve_type(module, type_path) {
+        let component_name = self.type_argument_name(module, generics, ty);
+
+        if !used_types
+          .iter()
+          .any(|used_type| used_type.component_name == component_name)
+        {
+          let arguments = self.type_arguments(module, generics, type_path, used_types);
+
+          used_types.push(UsedType {
+            qualified_name,
+            component_name: component_name.clone(),
+            arguments,
+          });
+        }
+
+        return Schema::reference(&component_name);
+      }
+    }
+
+    // serde_json::Value and types that weren't declared in the project accept any value.
+    ast::type_name(ty)
+      .and_then(|type_name| primitive_schema(&type_name))
+      .unwrap_or_default()
+  }
+
+  /// Schema of a tuple, serialized as an array: (String, u32) -> ["a", 1]
+  ///
+  /// OpenAPI 3.0 can't describe the type of each position, the items are one of the types
+  /// of the elements and the array has as many items as the tuple.
+  fn tuple_schema<'t>(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    types: impl Iterator<Item = &'t syn::Type>,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    let mut len = 0;
+    let mut item_schemas: Vec<Schema> = vec![];
+
+    for ty in types {
+      len += 1;
+
+      let schema = self.type_schema(module, generics, ty, used_types);
+      if !item_schemas.contains(&schema) {
+        item_schemas.push(schema);
+      }
+    }
+
+    // () is serialized as null.
+    if len == 0 {
+      return Schema::default();
+    }
+
+    let items = if item_schemas.len() == 1 {
+      item_schemas.remove(0)
+    } else {
+      Schema {
+        one_of: item_schemas,
+        ..Schema::default()
+      }
+    };
+
+    Schema {
+      min_items: Some(len),
+      max_items: Some(len),
+      ..Schema::array(items)
+    }
+  }
+
+  /// Type arguments of a use of a generic type: [User] in Page<User>
+  fn type_arguments(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    type_path: &syn::TypePath,
+    used_types: &mut Vec<UsedType>,
+  ) -> Vec<TypeArgument> {
+    let args = match type_path
+      .path
+      .segments
+      .last()
+      .map(|segment| &segment.arguments)
+    {
+      Some(syn::PathArguments::AngleBracketed(args)) => args,
+      _ => return vec![],
+    };
+
+    args
+      .args
+      .iter()
+      .filter_map(|arg| match arg {
+        syn::GenericArgument::Type(ty) => Some(TypeArgument {
+          name: self.type_argument_name(module, generics, ty),
+          schema: self.type_schema(module, generics, ty, used_types),
+        }),
+        _ => None,
+      })
+      .collect()
+  }
+
+  /// Name of a type in the name of components: User, PageUser for Page<User>, VecUser for Vec<User>
+  fn type_argument_name(&self, module: &[String], generics: &Generics, ty: &syn::Type) -> String {
+    if let Some(argument) = type_parameter(ty).and_then(|param| generics.get(&param)) {
+      return argument.name.clone();
+    }
+
+    if let Some(inner_type) = ast::wrapped_type(ty) {
+      return self.type_argument_name(module, generics, inner_type);
+    }
+
+    match ty {
+      syn::Type::Reference(reference) => self.type_argument_name(module, generics, &reference.elem),
+      syn::Type::Slice(slice) => format!(
+        "Vec{}",
+        self.type_argument_name(module, generics, &slice.elem)
+      ),
+      syn::Type::Array(array) => format!(
+        "Vec{}",
+        self.type_argument_name(module, generics, &array.elem)
+      ),
+      syn::Type::Tuple(tuple) => tuple
+        .elems
+        .iter()
+        .map(|elem| self.type_argument_name(module, generics, elem))
+        .collect(),
+      syn::Type::Path(type_path) => {
+        // Aliases are named after the type they stand for.
+        if let Some((qualified_name, alias)) = self.resolve_alias(module, type_path) {
+          let arguments = type_path
+            .path
+            .segments
+            .last()
+            .into_iter()
+            .flat_map(|segment| match &segment.arguments {
+              syn::PathArguments::AngleBracketed(args) => args.args.iter().collect(),
+              _ => vec![],
+            })
+            .filter_map(|arg| match arg {
+              syn::GenericArgument::Type(ty) => Some(TypeArgument {
+                name: self.type_argument_name(module, generics, ty),
+                schema: Schema::default(),
+              }),
+              _ => None,
+            })
+            .collect();
+
+          return self.type_argument_name(
+            &ast::parent_module(&qualified_name),
+            &bind_type_parameters(&alias.generics, arguments),
+            &alias.ty,
+          );
+        }
+
+        let segment = match type_path.path.segments.last() {
+          None => return String::new(),
+          Some(segment) => segment,
+        };
+
+        let name = match self.resolve_type(module, type_path) {
+          Some(qualified_name) => self.component_name(&qualified_name),
+          // u64 -> U64
+          None => {
+            let ident = segment.ident.to_string();
+            let mut chars = ident.chars();
+            match chars.next() {
+              None => ident,
+              Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+            }
+          }
+        };
+
+        let arguments: String = match &segment.arguments {
+          syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+              syn::GenericArgument::Type(ty) => Some(self.type_argument_name(module, generics, ty)),
+              _ => None,
+            })
+            .collect(),
+          _ => String::new(),
+        };
+
+        name + &arguments
+      }
+      _ => String::new(),
+    }
+  }
+
+  /// Schema of the component of a struct.
+  ///
+  /// `stack` holds the qualified names of the structs whose fields are being flattened.
+  fn struct_schema(
+    &self,
+    qualified_name: &str,
+    struct_: &ItemStruct,
+    generics: &Generics,
+    stack: &mut Vec<String>,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    // Field types are resolved from the module the struct was declared in.
+    let module = ast::parent_module(qualified_name);
+
+    // struct Email(String) is serialized as a String.
+    if let Some(field) = newtype_field(struct_) {
+      return self.type_schema(&module, generics, &field.ty, used_types);
+    }
+
+    let fields = match &struct_.fields {
+      syn::Fields::Named(fields) => fields,
+      syn::Fields::Unnamed(fields) => {
+        let types = serialized_tuple_fields(fields).map(|field| &field.ty);
+        return self.tuple_schema(&module, generics, types, used_types);
+      }
+      syn::Fields::Unit => return Schema::object(),
+    };
+
+    let mut schema =
+      self.fields_schema(&module, generics, &struct_.attrs, fields, stack, used_types);
+
+    // Missing fields get their #[serde(default)] value.
+    let rename_all = serde_attr::rename_all(&struct_.attrs);
+
+    for field in fields.named.iter() {
+      let ident = match &field.ident {
+        None => continue,
+        Some(ident) => ident.unraw().to_string(),
+      };
+
+      let name = serde_attr::field_attrs(&field.attrs, &ident, rename_all)
+        .name
+        .deserialize;
+
+      if let Some(property) = schema.properties.get_mut(&name) {
+        property.default = self.field_default_value(qualified_name, struct_, field);
+      }
+    }
+
+    schema
+  }
+
+  /// Object with a property for each field: struct Foo { .. } and Variant { .. }
+  ///
+  /// `container_attrs` are the attributes of the struct or variant, their #[serde(rename_all)]
+  /// renames the fields. A field that is only serialized is readOnly, a field that is only
+  /// deserialized is writeOnly.
+  ///
+  /// The properties of #[serde(flatten)] structs are merged with the other properties,
+  /// flattened maps are the additionalProperties and other flattened types are in allOf.
+  fn fields_schema(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    container_attrs: &[syn::Attribute],
+    fields: &syn::FieldsNamed,
+    stack: &mut Vec<String>,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    let rename_all = serde_attr::rename_all(container_attrs);
+    let container_default = serde_attr::default_attribute(container_attrs).is_some();
+
+    let mut schema = Schema::object();
+    let mut all_of = vec![];
+
+    if serde_attr::deny_unknown_fields(container_attrs) {
+      schema.additional_properties = Some(AdditionalProperties::Allowed(false));
+    }
+
+    for field in fields.named.iter() {
+      let ident = match &field.ident {
+        None => continue,
+        // r#type -> type
+        Some(ident) => ident.unraw().to_string(),
+      };
+
+      let attrs = serde_attr::field_attrs(&field.attrs, &ident, rename_all);
+
+      // PhantomData<T> holds no value.
+      let is_phantom_data = ast::type_name(&field.ty).as_deref() == Some("PhantomData");
+
+      if (attrs.skip_serializing && attrs.skip_deserializing) || is_phantom_data {
+        continue;
+      }
+
+      if attrs.flatten {
+        let flattened = self.flattened_schema(module, generics, &field.ty, stack, used_types);
+
+        if flattened.r#type.as_deref() == Some("object") {
+          schema.properties.extend(flattened.properties);
+          schema.required.extend(flattened.required);
+
+          if flattened.additional_properties.is_some() {
+            schema.additional_properties = flattened.additional_properties;
+          }
+        } else {
+          all_of.push(flattened);
+        }
+
+        continue;
+      }
+
+      let mut property = self.type_schema(module, generics, &field.ty, used_types);
+      property.description = docs::description(&field.attrs);
+
+      // None is serialized as null unless it is skipped by skip_serializing_if.
+      let is_option = ast::generic_type_argument(&field.ty, "Option").is_some();
+      property.nullable = is_option;
+
+      // Whether the field is always in serialized values and must be in deserialized ones.
+      let serialize_required = !attrs.skip_serializing_if;
+      let deserialize_required =
+        !is_option && !container_default && serde_attr::default_attribute(&field.attrs).is_none();
+
+      let name = attrs.name;
+
+      if !attrs.skip_serializing && !attrs.skip_deserializing && name.serialize == name.deserialize
+      {
+        schema.add_property(
+          name.serialize,
+          property.clone(),
+          serialize_required && deserialize_required,
+        );
+      } else {
+        if !attrs.skip_serializing {
+          let property = Schema {
+            read_only: true,
+            ..property.clone()
+          };
+          schema.add_property(name.serialize, property, serialize_required);
+        }
+
+        if !attrs.skip_deserializing {
+          let property = Schema {
+            write_only: true,
+            ..property.clone()
+          };
+          schema.add_property(name.deserialize.clone(), property, deserialize_required);
+        }
+      }
+
+      if !attrs.skip_deserializing {
+        for alias in attrs.aliases {
+          let property = Schema {
+            description: Some(format!("Alias of `{}`.", name.deserialize)),
+            write_only: true,
+            ..property.clone()
+          };
+          schema.add_property(alias, property, false);
+        }
+      }
+    }
+
+    if all_of.is_empty() {
+      return schema;
+    }
+
+    if !schema.properties.is_empty() || schema.additional_properties.is_some() {
+      all_of.push(schema);
+    }
+
+    Schema {
+      all_of,
+      ..Schema::default()
+    }
+  }
+
+  /// Schema of a #[serde(flatten)] field: an object with the fields of a struct, the schema
+  /// of the type otherwise.
+  fn flattened_schema(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    ty: &syn::Type,
+    stack: &mut Vec<String>,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    // The fields of Option<T> are optional.
+    if let Some(inner_type) = ast::generic_type_argument(ty, "Option") {
+      let mut schema = self.flattened_schema(module, generics, inner_type, stack, used_types);
+      schema.required.clear();
+      return schema;
+    }
+
+    if let Some(inner_type) = ast::wrapped_type(ty) {
+      return self.flattened_schema(module, generics, inner_type, stack, used_types);
+    }
+
+    if let syn::Type::Path(type_path) = ty {
+      let struct_ = self
+        .resolve_struct(module, type_path)
+        // A struct that flattens itself is referred to with a $ref the second time.
+        .filter(|(qualified_name, _)| !stack.contains(qualified_name));
+
+      if let Some((qualified_name, struct_)) = struct_ {
+        let arguments = self.type_arguments(module, generics, type_path, used_types);
+        let struct_generics = bind_type_parameters(&struct_.generics, arguments);
+
+        stack.push(qualified_name.clone());
+        let schema = self.struct_schema(
+          &qualified_name,
+          &struct_,
+          &struct_generics,
+          stack,
+          used_types,
+        );
+        stack.pop();
+
+        return schema;
+      }
+    }
+
+    self.type_schema(module, generics, ty, used_types)
+  }
+
+  /// Schema of the component of an enum.
+  ///
+  /// Enums whose variants are all unit variants are strings, other enums are one of their
+  /// variants serialized the way #[serde(tag)], #[serde(content)] and #[serde(untagged)] say.
+  fn enum_schema(
+    &self,
+    qualified_name: &str,
+    enum_: &ItemEnum,
+    generics: &Generics,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    let module = ast::parent_module(qualified_name);
+    let representation = serde_attr::enum_representation(&enum_.attrs);
+    let rename_all = serde_attr::rename_all(&enum_.attrs);
+
+    let variants: Vec<&syn::Variant> = enum_
+      .variants
+      .iter()
+      .filter(|variant| !serde_attr::is_skipped(&variant.attrs))
+      .collect();
+
+    let variant_name = |variant: &syn::Variant| {
+      serde_attr::variant_name(&variant.attrs, &variant.ident.to_string(), rename_all).serialize
+    };
+
+    let is_unit_only = variants
+      .iter()
+      .all(|variant| matches!(variant.fields, syn::Fields::Unit));
+
+    if is_unit_only && representation == EnumRepresentation::External {
+      return Schema::string_enum(
+        variants
+          .iter()
+          .map(|variant| variant_name(variant))
+          .collect(),
+      );
+    }
+
+    Schema {
+      one_of: variants
+        .iter()
+        .map(|variant| {
+          self.variant_schema(
+            &module,
+            generics,
+            &representation,
+            variant_name(variant),
+            variant,
+            used_types,
+          )
+        })
+        .collect(),
+      ..Schema::default()
+    }
+  }
+
+  /// Schema of the values of an enum variant.
+  ///
+  /// External: "Unit", {"Newtype": T}, {"Struct": {"a": 1}}
+  /// Internal: {"tag": "Unit"}, {"tag": "Struct", "a": 1}, {"tag": "Newtype", ..T}
+  /// Adjacent: {"tag": "Unit"}, {"tag": "Newtype", "content": T}
+  /// Untagged: null, T, {"a": 1}
+  fn variant_schema(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    representation: &EnumRepresentation,
+    name: String,
+    variant: &syn::Variant,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    let is_unit = matches!(variant.fields, syn::Fields::Unit);
+
+    let mut schema = match representation {
+      EnumRepresentation::External if is_unit => Schema::string_enum(vec![name]),
+      EnumRepresentation::External => {
+        let mut schema = Schema::object();
+        schema.add_property(
+          name,
+          self.variant_fields_schema(module, generics, variant, used_types),
+          true,
+        );
+        schema
+      }
+      EnumRepresentation::Internal { tag } => {
+        let mut tag_schema = Schema::object();
+        tag_schema.add_property(tag.clone(), Schema::string_enum(vec![name.clone()]), true);
+
+        match &variant.fields {
+          syn::Fields::Unit => tag_schema,
+          syn::Fields::Named(fields) => {
+            let mut schema = self.fields_schema(
+              module,
+              generics,
+              &variant.attrs,
+              fields,
+              &mut vec![],
+              used_types,
+            );
+            schema.required.insert(0, tag.clone());
+            schema.properties.extend(tag_schema.properties);
+            schema
+          }
+          // The fields of the newtype are next to the tag.
+          syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Schema {
+            all_of: vec![
+              tag_schema,
+              self.type_schema(module, generics, &fields.unnamed[0].ty, used_types),
+            ],
+            ..Schema::default()
+          },
+          syn::Fields::Unnamed(_) => {
+            tracing::warn!(
+              "tuple variant {} can't be serialized with #[serde(tag = \"{}\")]",
+              name,
+              tag
+            );
+            tag_schema
+          }
+        }
+      }
+      EnumRepresentation::Adjacent { tag, content } => {
+        let mut schema = Schema::object();
+        schema.add_property(tag.clone(), Schema::string_enum(vec![name]), true);
+
+        if !is_unit {
+          schema.add_property(
+            content.clone(),
+            self.variant_fields_schema(module, generics, variant, used_types),
+            true,
+          );
+        }
+
+        schema
+      }
+      EnumRepresentation::Untagged if is_unit => Schema {
+        nullable: true,
+        r#enum: vec![serde_json::Value::Null],
+        ..Schema::default()
+      },
+      EnumRepresentation::Untagged => {
+        self.variant_fields_schema(module, generics, variant, used_types)
+      }
+    };
+
+    schema.description = docs::description(&variant.attrs);
+
+    schema
+  }
+
+  /// Schema of the fields of a variant: T in Newtype(T), an array for Tuple(A, B)
+  /// and an object for Struct { .. }
+  fn variant_fields_schema(
+    &self,
+    module: &[String],
+    generics: &Generics,
+    variant: &syn::Variant,
+    used_types: &mut Vec<UsedType>,
+  ) -> Schema {
+    match &variant.fields {
+      syn::Fields::Named(fields) => self.fields_schema(
+        module,
+        generics,
+        &variant.attrs,
+        fields,
+        &mut vec![],
+        used_types,
+      ),
+      syn::Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+        self.type_schema(module, generics, &fields.unnamed[0].ty, used_types)
+      }
+      syn::Fields::Unnamed(fields) => {
+        let types = serialized_tuple_fields(fields).map(|field| &field.ty);
+        self.tuple_schema(module, generics, types, used_types)
+      }
+      syn::Fields::Unit => Schema::default(),
+    }
+  }
+
+  /// Returns the qualified name and declaration of the `type` alias `type_path` refers to in `module`.
+  fn resolve_alias(
+    &self,
+    module: &[String],
+    type_path: &syn::TypePath,
+  ) -> Option<(String, &ItemType)> {
+    let qualified_name = self
+      .symbols
+      .resolve(module, &ast::path_segments(&type_path.path))?;
+    let alias = self.type_aliases.get(&qualified_name)?;
+
+    Some((qualified_name, alias))
+  }
+
+  /// Returns the qualified name of the struct or enum `type_path` refers to in `module`.
+  fn resolve_type(&self, module: &[String], type_path: &syn::TypePath) -> Option<String> {
+    self
+      .symbols
+      .resolve(module, &ast::path_segments(&type_path.path))
+      .filter(|qualified_name| {
+        self.structs.contains_key(qualified_name) || self.enums.contains_key(qualified_name)
+      })
+  }
+
+  /// Returns the qualified name and declaration of the struct `type_path` refers to in `module`.
+  fn resolve_struct(
+    &self,
+    module: &[String],
+    type_path: &syn::TypePath,
+  ) -> Option<(String, ItemStruct)> {
+    let qualified_name = self
+      .symbols
+      .resolve(module, &ast::path_segments(&type_path.path))?;
+
+    self
+      .structs
+      .get(&qualified_name)
+      .cloned()
+      .map(|struct_| (qualified_name, struct_))
+  }
+
+  /// Returns the qualified name and declaration of the enum `type_path` refers to in `module`.
+  fn resolve_enum(
+    &self,
+    module: &[String],
+    type_path: &syn::TypePath,
+  ) -> Option<(String, ItemEnum)> {
+    let qualified_name = self
+      .symbols
+      .resolve(module, &ast::path_segments(&type_path.path))?;
+
+    self
+      .enums
+      .get(&qualified_name)
+      .cloned()
+      .map(|enum_| (qualified_name, enum_))
+  }
+
+  /// Value a missing field of the struct `qualified_name` gets because of #[serde(default)],
+  /// None when the field has no default or its value can't be found in the source code.
+  fn field_default_value(
+    &self,
+    qualified_name: &str,
+    struct_: &ItemStruct,
+    field: &syn::Field,
+  ) -> Option<serde_json::Value> {
+    let module = ast::parent_module(qualified_name);
+
+    match serde_attr::default_attribute(&field.attrs) {
+      Some(DefaultValue::Trait) => type_default_value(&field.ty),
+      Some(DefaultValue::Function(path)) => self
+        .default_function_expr(&module, &path)
+        .and_then(ast::literal_value),
+      // #[serde(default)] on the struct, missing fields get the value they have in the struct's default.
+      None => {
+        let container_default = serde_attr::default_attribute(&struct_.attrs)?;
+
+        let default_expr = match container_default {
+          DefaultValue::Trait => self.default_impl_expr(qualified_name),
+          DefaultValue::Function(path) => self.default_function_expr(&module, &path),
+        };
+
+        match default_expr {
+          // #[derive(Default)]
+          None => type_default_value(&field.ty),
+          // Self { limit: 10, offset: 0 }
+          Some(syn::Expr::Struct(expr_struct)) => expr_struct
+            .fields
+            .iter()
+            .find(|field_value| match (&field_value.member, &field.ident) {
+              (syn::Member::Named(member), Some(ident)) => member == ident,
+              _ => false,
+            })
+            .and_then(|field_value| ast::literal_value(&field_value.expr)),
+          Some(_) => None,
+        }
+      }
+    }
+  }
+
+  /// Expression returned by the function `path` used in #[serde(default = "path")].
+  fn default_function_expr(&self, module: &[String], path: &str) -> Option<&syn::Expr> {
+    let path: Vec<String> = path.split("::").map(String::from).collect();
+
+    self
+      .symbols
+      .resolve(module, &path)
+      .and_then(|func_name| self.fn_declarations.get(&func_name))
+      .and_then(|func| ast::tail_expr(&func.block))
+  }
+
+  /// Expression returned by `default` in `impl Default for Struct`.
+  fn default_impl_expr(&self, qualified_name: &str) -> Option<&syn::Expr> {
+    self
+      .trait_impl_method(qualified_name, "Default", "default")
+      .and_then(|(_, method)| ast::tail_expr(&method.block))
+  }
+
+  /// Method `method_name` of `impl Trait for Type`, where Type is `qualified_name`,
+  /// and the module the impl was declared in.
+  fn trait_impl_method(
+    &self,
+    qualified_name: &str,
+    trait_name: &str,
+    method_name: &str,
+  ) -> Option<(&[String], &syn::ImplItemMethod)> {
+    self.impls.iter().find_map(|(module, impl_)| {
+      let is_trait_impl = impl_
+        .trait_
+        .as_ref()
+        .and_then(|(_, path, _)| path.segments.last())
+        .map(|segment| segment.ident == trait_name)
+        .unwrap_or(false);
+
+      let self_name = match &*impl_.self_ty {
+        syn::Type::Path(type_path) => self
+          .symbols
+          .resolve(module, &ast::path_segments(&type_path.path)),
+        _ => None,
+      };
+
+      if !is_trait_impl || self_name.as_deref() != Some(qualified_name) {
+        return None;
+      }
+
+      impl_.items.iter().find_map(|item| match item {
+        ImplItem::Method(method) if method.sig.ident == method_name => {
+          Some((module.as_slice(), method))
+        }
+        _ => None,
+      })
+    })
+  }
+
+  /// Schema of a path or query parameter of type `ty`, declared in `module`.
+  fn parameter_schema(
+    &self,
+    module: &[String],
+    ty: &syn::Type,
+    default: Option<serde_json::Value>,
+  ) -> Schema {
+    if let Some(item_type) = ast::generic_type_argument(ty, "Vec") {
+      return Schema::array(self.parameter_schema(module, item_type, None));
+    }
+
+    if let syn::Type::Path(type_path) = ty {
+      // type UserId = Uuid;
+      if let Some((qualified_name, alias)) = self.resolve_alias(module, type_path) {
+        return self.parameter_schema(&ast::parent_module(&qualified_name), &alias.ty, default);
+      }
+
+      // struct UserId(Uuid);
+      if let Some((qualified_name, struct_)) = self.resolve_struct(module, type_path) {
+        if let Some(field) = newtype_field(&struct_) {
+          return self.parameter_schema(&ast::parent_module(&qualified_name), &field.ty, default);
+        }
+      }
+
+      // Unit variants are serialized as strings.
+      if let Some((_, enum_)) = self.resolve_enum(module, type_path) {
+        let rename_all = serde_attr::rename_all(&enum_.attrs);
+
+        let variants = enum_
+          .variants
+          .iter()
+          .filter(|variant| matches!(variant.fields, syn::Fields::Unit))
+          .filter(|variant| !serde_attr::is_skipped(&variant.attrs))
+          .map(|variant| {
+            serde_attr::variant_name(&variant.attrs, &variant.ident.to_string(), rename_all)
+              .deserialize
+          })
+          .collect();
+
+        return Schema {
+          default: default.filter(serde_json::Value::is_string),
+          ..Schema::string_enum(variants)
+        };
+      }
+    }
+
+    let schema = ast::type_name(ty)
+      .and_then(|type_name| primitive_schema(&type_name))
+      .unwrap_or_else(|| Schema::primitive("string"));
+
+    Schema { default, ..schema }
+  }
+
+  /// Builds a query parameter for each field of T in Query<T>.
+  fn build_query_parameters(&self, module: &[String], controller_fn: &ItemFn) -> Vec<Parameter> {
+    let extractor_type = match controller_fn
+      .sig
+      .inputs
+      .iter()
+      .find_map(|arg| ast::extractor_type(arg, "Query"))
+    {
+      None => return vec![],
+      Some(extractor_type) => extractor_type,
+    };
+
+    let (qualified_name, struct_) = match extractor_type {
+      syn::Type::Path(type_path) => match self.resolve_struct(module, type_path) {
+        None => {
+          tracing::debug!(
+            "query params {:?} aren't a struct of the project",
+            type_path
+          );
+          return vec![];
+        }
+        Some(struct_) => struct_,
+      },
+      _ => return vec![],
+    };
+
+    // Field types are resolved from the module the struct was declared in.
+    let struct_module = ast::parent_module(&qualified_name);
+    let rename_all = serde_attr::rename_all(&struct_.attrs);
+
+    struct_
+      .fields
+      .iter()
+      .filter_map(|field| {
+        let ident = field.ident.as_ref()?.unraw().to_string();
+        let attrs = serde_attr::field_attrs(&field.attrs, &ident, rename_all);
+
+        if attrs.skip_deserializing {
+          return None;
+        }
+
+        let name = attrs.name.deserialize;
+
+        let option_type = ast::generic_type_argument(&field.ty, "Option");
+
+        let has_default = serde_attr::default_attribute(&field.attrs).is_some()
+          || serde_attr::default_attribute(&struct_.attrs).is_some();
+
+        let schema = self.parameter_schema(
+          &struct_module,
+          option_type.unwrap_or(&field.ty),
+          self.field_default_value(&qualified_name, &struct_, field),
+        );
+
+        Some(Parameter {
+          name,
+          r#in: String::from("query"),
+          description: None,
+          required: option_type.is_none() && !has_default,
+          // ?tags=a&tags=b
+          explode: schema.r#type.as_deref() == Some("array"),
+          schema,
+        })
+      })
+      .collect()
   }
 
-  fn handle_method_call(&mut self, method_call: ExprMethodCall) {
-    // Calling Router.route("/path", method(controller))
-    if method_call.method != "route" {
-      return;
-    }
+  /// Builds a parameter for each :param and *wildcard of `route`.
+  ///
+  /// The schemas come from the Path extractor of the handler:
+  ///
+  /// Path<u64> -> the only parameter is an integer
+  /// Path<(Uuid, u32)> -> the parameters in the order they appear in the route
+  /// Path<Params> -> the fields of Params with the same name as the parameters
+  fn build_path_parameters(
+    &self,
+    route: &str,
+    module: &[String],
+    controller_fn: &ItemFn,
+  ) -> Vec<Parameter> {
+    let (_, params) = axum::path_template(route);
+
+    let extractor_type = controller_fn
+      .sig
+      .inputs
+      .iter()
+      .find_map(|arg| ast::extractor_type(arg, "Path"));
+
+    let param_types: Vec<Option<syn::Type>> = match extractor_type {
+      None => vec![None; params.len()],
+      Some(syn::Type::Tuple(tuple)) => (0..params.len())
+        .map(|i| tuple.elems.iter().nth(i).cloned())
+        .collect(),
+      Some(syn::Type::Path(type_path)) => match self.resolve_struct(module, type_path) {
+        None if params.len() == 1 => vec![extractor_type.cloned()],
+        None => vec![None; params.len()],
+        Some((_, struct_)) => {
+          let rename_all = serde_attr::rename_all(&struct_.attrs);
 
-    let route = match &method_call.args[0] {
-      Expr::Lit(lit) => match &lit.lit {
-        syn::Lit::Str(path) => path.value(),
-        _ => return,
+          params
+            .iter()
+            .map(|param| {
+              struct_
+                .fields
+                .iter()
+                .find(|field| match &field.ident {
+                  None => false,
+                  // The parameter is deserialized into the field with the same serde name.
+                  Some(ident) => {
+                    serde_attr::field_attrs(&field.attrs, &ident.unraw().to_string(), rename_all)
+                      .name
+                      .deserialize
+                      == param.name
+                  }
+                })
+                .map(|field| field.ty.clone())
+            })
+            .collect()
+        }
       },
-      _ => return,
+      Some(_) => vec![None; params.len()],
     };
 
-    let (method, controller) = match &method_call.args[1] {
-      Expr::Call(call) => {
-        let method = match &*call.func {
-          Expr::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
-          _ => return,
-        };
+    params
+      .into_iter()
+      .zip(param_types)
+      .map(|(param, ty)| Parameter {
+        name: param.name,
+        r#in: String::from("path"),
+        description: if param.wildcard {
+          Some(String::from(
+            "Matches the rest of the path, including slashes.",
+          ))
+        } else {
+          None
+        },
+        required: true,
+        explode: false,
+        schema: match ty {
+          Some(ty) if !param.wildcard => self.parameter_schema(module, &ty, None),
+          _ => Schema::primitive("string"),
+        },
+      })
+      .collect()
+  }
 
-        let controller = match call.args.last().unwrap() {
-          Expr::Path(path) => path.path.segments.last().unwrap().ident.to_string(),
-          _ => return,
-        };
+  /// Returns the qualified name and declaration of the function that handles a route.
+  fn resolve_handler(&self, handler: &Handler) -> Option<(String, &ItemFn)> {
+    let handler_name = self
+      .symbols
+      .resolve(&handler.module, &handler.handler_path)?;
 
-        (method, controller)
-      }
+    self
+      .fn_declarations
+      .get(&handler_name)
+      .map(|controller_fn| (handler_name, controller_fn))
+  }
 
-      _ => return,
-    };
+  /// Builds the request body of a handler that has a Json<T> extractor.
+  ///
+  /// The body is optional when the extractor is wrapped in an Option: Option<Json<T>>
+  fn build_request_body(&self, module: &[String], controller_fn: &ItemFn) -> Option<RequestBody> {
+    let (body_type, required) = request_body_type(controller_fn)?;
 
-    self.routes.insert(
-      route,
-      RouteHandler {
-        method,
-        handler_name: controller,
+    Some(RequestBody {
+      required,
+      content: Content {
+        content_type: ContentType::new(self.body_schema(module, body_type)),
       },
-    );
+    })
   }
 
-  pub fn build_type_components(&mut self) {
-    for used_type in self.used_types.clone().iter() {
-      let struct_ = self.structs.get(used_type).cloned();
-      match struct_ {
-        // It is a type that wasn't defined in the project.
-        None => {
-          if let Some(inner_type) = OPTION_REGEX
-            .captures(used_type)
-            .and_then(|capture| capture.get(1))
-          {
-            self.components.insert(
-              inner_type.as_str().to_owned(),
-              vec![Component {
-                r#type: inner_type.as_str().to_owned(),
-                required: false,
-                field_name: None,
-              }],
-            );
-          } else {
-            self.components.insert(
-              used_type.clone(),
-              vec![Component {
-                r#type: used_type.clone(),
-                required: false,
-                field_name: None,
-              }],
-            );
-          }
-        }
-        Some(struct_) => {
-          self.build_type_components_from_struct(&struct_);
-        }
-      }
+  /// Schema of the body T in Json<T>, declared in `module`.
+  fn body_schema(&self, module: &[String], ty: &syn::Type) -> Schema {
+    // The components of the types were built from the used types.
+    self.type_schema(module, &Generics::new(), ty, &mut vec![])
+  }
+
+  /// Return type of a handler declared in `module` with its aliases expanded, and the module
+  /// the types of the expanded alias are declared in:
+  ///
+  /// type AppResult<T> = Result<T, AppError>;
+  /// AppResult<Json<User>> -> Result<Json<User>, AppError>
+  fn handler_return_type(
+    &self,
+    module: &[String],
+    controller_fn: &ItemFn,
+  ) -> Option<(syn::Type, Vec<String>)> {
+    match &controller_fn.sig.output {
+      syn::ReturnType::Default => None,
+      syn::ReturnType::Type(_, ty) => Some(self.expand_alias(module, ty)),
     }
   }
 
-  fn build_type_components_from_struct(&mut self, struct_: &ItemStruct) {
-    let struct_name = struct_.ident.to_string();
+  /// Expands `ty` while it is an alias, the arguments of the alias replace its type parameters.
+  fn expand_alias(&self, module: &[String], ty: &syn::Type) -> (syn::Type, Vec<String>) {
+    let type_path = match ty {
+      syn::Type::Path(type_path) => type_path,
+      _ => return (ty.clone(), module.to_vec()),
+    };
 
-    self.components.insert(struct_name.clone(), vec![]);
+    match self.resolve_alias(module, type_path) {
+      None => (ty.clone(), module.to_vec()),
+      Some((qualified_name, alias)) => {
+        let arguments = alias
+          .generics
+          .type_params()
+          .map(|param| param.ident.to_string())
+          .zip(ast::type_arguments(type_path).into_iter().cloned())
+          .collect();
 
-    for field in struct_.fields.iter() {
-      let field_name = field.ident.clone().unwrap().to_string();
+        let mut alias_type = (*alias.ty).clone();
+        ast::substitute_type_parameters(&mut alias_type, &arguments);
 
-      self.build_type_components_from_type(&struct_name, &field_name, &field.ty);
+        self.expand_alias(&ast::parent_module(&qualified_name), &alias_type)
+      }
     }
   }
 
-  fn build_type_components_from_type(
-    &mut self,
-    struct_name: &str,
-    field_name: &str,
-    ty: &syn::Type,
-  ) {
-    match ty {
-      syn::Type::Array(_) => todo!(),
-      syn::Type::BareFn(_) => todo!(),
-      syn::Type::Group(_) => todo!(),
-      syn::Type::ImplTrait(_) => todo!(),
-      syn::Type::Infer(_) => todo!(),
-      syn::Type::Macro(_) => todo!(),
-      syn::Type::Never(_) => todo!(),
-      syn::Type::Paren(_) => todo!(),
-      syn::Type::Ptr(_) => todo!(),
-      syn::Type::Reference(_) => todo!(),
-      syn::Type::Slice(_) => todo!(),
-      syn::Type::TraitObject(_) => todo!(),
-      syn::Type::Verbatim(_) => todo!(),
-      syn::Type::Path(type_path) => {
-        let segment = type_path
-          .path
-          .segments
-          .iter()
-          .map(|segment| {
-            let ident = segment.ident.to_string();
+  /// Qualified name of the error type E of a handler that returns Result<T, E> declared in `module`,
+  /// the module `impl IntoResponse for E` was declared in and the body of its `into_response`.
+  fn error_impl(
+    &self,
+    module: &[String],
+    output: &syn::Type,
+  ) -> Option<(String, &[String], &syn::Block)> {
+    let error_name = match response::error_type(output)? {
+      syn::Type::Path(type_path) => self
+        .symbols
+        .resolve(module, &ast::path_segments(&type_path.path))?,
+      _ => return None,
+    };
 
-            let arguments = match &segment.arguments {
-              syn::PathArguments::None => vec![],
-              syn::PathArguments::AngleBracketed(args) => args
-                .args
-                .iter()
-                .map(|arg| match arg {
-                  syn::GenericArgument::Lifetime(_) => todo!(),
-                  syn::GenericArgument::Binding(_) => todo!(),
-                  syn::GenericArgument::Constraint(_) => todo!(),
-                  syn::GenericArgument::Const(_) => todo!(),
-                  syn::GenericArgument::Type(typ) => match typ {
-                    syn::Type::Path(type_path) => type_path
-                      .path
-                      .segments
-                      .iter()
-                      .map(|segment| segment.ident.to_string())
-                      .collect::<Vec<_>>()
-                      .join("::"),
-                    _ => todo!(),
-                  },
-                })
-                .collect(),
-              syn::PathArguments::Parenthesized(_) => todo!(),
-            };
-
-            if arguments.is_empty() {
-              ident
-            } else {
-              format!("{}<{}>", ident, arguments.join(", "))
-            }
+    let (impl_module, method) =
+      self.trait_impl_method(&error_name, "IntoResponse", "into_response")?;
+
+    Some((error_name, impl_module, &method.block))
+  }
+
+  /// Qualified name of the struct `path` refers to in `module`: ErrorBody in Json(ErrorBody { .. })
+  fn json_struct_name(&self, module: &[String], path: &syn::Path) -> Option<String> {
+    self
+      .symbols
+      .resolve(module, &ast::path_segments(path))
+      .filter(|qualified_name| self.structs.contains_key(qualified_name))
+  }
+
+  /// Builds a response for each status code the handler may respond with.
+  ///
+  /// Handlers that return Result<T, E> also respond with the error responses of `impl IntoResponse for E`,
+  /// they share the schema of the error body.
+  fn build_responses(
+    &self,
+    module: &[String],
+    controller_fn: &ItemFn,
+  ) -> BTreeMap<String, Response> {
+    let mut responses = BTreeMap::new();
+
+    let return_type = self.handler_return_type(module, controller_fn);
+    let output = return_type.as_ref().map(|(output, _)| output);
+
+    let error_responses = return_type
+      .as_ref()
+      .and_then(|(output, output_module)| self.error_impl(output_module, output))
+      .map(|(error_name, impl_module, into_response)| {
+        response::into_response_responses(into_response)
+          .into_iter()
+          .map(|error_response| {
+            let content =
+              self.response_content(impl_module, &error_response.body, Some(&error_name));
+            (error_response.status, content)
           })
           .collect::<Vec<_>>()
-          .join("::");
+      })
+      .unwrap_or_default();
 
-        println!(
-          "aaaaaa struct_name={} field_name={}, segment={:?}",
-          struct_name, field_name, segment
-        );
+    let handler_responses = response::handler_responses(output, &controller_fn.block)
+      .into_iter()
+      .map(|handler_response| {
+        let content = self.response_content(module, &handler_response.body, None);
+        (handler_response.status, content)
+      });
 
-        match self.structs.get(&segment).cloned() {
-          None => {
-            println!(
-              "aaaaaa segment is primitive struct_name={} segment={}",
-              struct_name, segment
-            );
-            self
-              .components
-              .get_mut(struct_name)
-              .unwrap()
-              .push(Component {
-                required: {
-                  let required = !OPTION_REGEX.is_match(&segment);
-                  println!("aaaaaa field is required field_name={}", segment);
-                  required
-                },
-                field_name: Some(field_name.to_string()),
-                r#type: OPTION_REGEX
-                  .captures(&segment)
-                  .and_then(|capture| capture.get(1))
-                  .map(|matches| matches.as_str().to_string())
-                  .unwrap_or_else(|| segment),
-              });
-          }
-          Some(struct_) => {
-            // We found a field that has a struct type:
-            //
-            // struct S1 {
-            //   field_1: i32,
-            //   field_2: S2 <-- here
-            // }
-            //
-            // struct S2 {
-            //   field_1: String
-            // }
-            //
-            // So we add a definition for field2 and recurse into the struct S2.
-            let field_struct_name = struct_.ident.to_string();
-            self
-              .components
-              .get_mut(struct_name)
-              .unwrap()
-              .push(Component {
-                required: !OPTION_REGEX.is_match(&field_struct_name),
-                r#type: field_struct_name,
-                field_name: Some(field_name.to_string()),
-              });
-            self.build_type_components_from_struct(&struct_);
-          }
-        }
+    for (status, content) in handler_responses.chain(error_responses) {
+      let description = String::from(axum::reason_phrase(status));
+
+      let response = match content {
+        None => Response::DescriptionOnly { description },
+        Some((content_type, schema)) => Response::Content {
+          description,
+          content: BTreeMap::from([(String::from(content_type), ContentType::new(schema))]),
+        },
+      };
+
+      // The first response found for a status code is kept.
+      responses.entry(status.to_string()).or_insert(response);
+    }
+
+    responses
+  }
+
+  /// Content type and schema of a response body, None for responses without a body.
+  ///
+  /// json!({ .. }) bodies of `impl IntoResponse for E` refer to the component named after E.
+  fn response_content(
+    &self,
+    module: &[String],
+    body: &response::Body,
+    error_name: Option<&str>,
+  ) -> Option<(&'static str, Schema)> {
+    match body {
+      response::Body::Empty | response::Body::Unknown => None,
+      response::Body::Json(body_type) => {
+        Some(("application/json", self.body_schema(module, body_type)))
+      }
+      response::Body::JsonStruct(path) => {
+        let schema = match self.json_struct_name(module, path) {
+          None => Schema::default(),
+          Some(qualified_name) => Schema::reference(&self.component_name(&qualified_name)),
+        };
+
+        Some(("application/json", schema))
+      }
+      response::Body::JsonObject(_) => {
+        let schema = match error_name {
+          None => Schema::default(),
+          Some(error_name) => Schema::reference(&self.component_name(error_name)),
+        };
+
+        Some(("application/json", schema))
       }
-      syn::Type::Tuple(tuple_struct) => {
-        for elem in tuple_struct.elems.iter() {
-          self.build_type_components_from_type(struct_name, field_name, elem);
+      response::Body::Html => Some(("text/html", Schema::primitive("string"))),
+      response::Body::Text => Some(("text/plain", Schema::primitive("string"))),
+    }
+  }
+
+  /// Builds the operation of a route handled by `controller_fn`.
+  fn build_path(&self, route: &str, handler_name: &str, controller_fn: &ItemFn) -> Path {
+    // Types used by the handler are resolved from the module it was declared in.
+    let module = ast::parent_module(handler_name);
+
+    let mut parameters = self.build_path_parameters(route, &module, controller_fn);
+    parameters.extend(self.build_query_parameters(&module, controller_fn));
+
+    // The first paragraph of the handler's /// comments is the summary.
+    let docs = docs::parse(handler_name, &controller_fn.attrs);
+
+    let mut responses = self.build_responses(&module, controller_fn);
+
+    // # Responses in the documentation describe the status codes the handler responds with.
+    for (status, description) in docs.responses {
+      match responses.get_mut(&status) {
+        Some(response) => response.set_description(description),
+        None => {
+          responses.insert(status, Response::DescriptionOnly { description });
         }
       }
-      _ => todo!(),
+    }
+
+    let mut request_body = self.build_request_body(&module, controller_fn);
+
+    if !docs.request_examples.is_empty() {
+      match &mut request_body {
+        None => tracing::warn!(
+          "the request example of {} was skipped, the handler has no Json body",
+          handler_name
+        ),
+        Some(request_body) => request_body
+          .content
+          .content_type
+          .set_examples(docs.request_examples),
+      }
+    }
+
+    // # Example and # Response example are examples of the successful response.
+    let response_examples: Vec<docs::Example> = docs
+      .response_examples
+      .into_iter()
+      .chain(docs.examples)
+      .collect();
+
+    if !response_examples.is_empty() {
+      match responses
+        .iter_mut()
+        .find(|(status, _)| status.starts_with('2'))
+      {
+        None => tracing::warn!(
+          "the response example of {} was skipped, the handler has no successful response",
+          handler_name
+        ),
+        Some((_, response)) => response.set_examples(response_examples),
+      }
+    }
+
+    Path {
+      tags: docs.tags,
+      summary: docs.summary,
+      parameters,
+      request_body,
+      description: docs.description,
+      operation_id: docs.operation_id,
+      responses,
+      deprecated: docs.deprecated,
     }
   }
 
@@ -322,123 +1849,54 @@ impl AstTraverser {
       },
       servers: vec![],
       paths: {
-        let mut paths = HashMap::new();
-
-        for (route, controller) in self.routes.iter() {
-          let controller_fn = self.fn_declarations.get(&controller.handler_name).unwrap();
-          paths.insert(
-            route.clone(),
-            HashMap::from([(
-              controller.method.clone(),
-              Path {
-                summary: Some(String::from("TODO")),
-                parameters: {
-                  let parameters = Vec::new();
-
-                for parameter in controller_fn
-                .sig
-                .inputs
-                .iter()
-                .filter(|parameter| axum::is_query_param(parameter)) {
-                  parameters
-                }
-                controller_fn
-                  .sig
-                  .inputs
-                  .iter()
-                  .filter(|parameter| axum::is_query_param(parameter))
-                  .map(|parameter| Parameter {
-                    name: ast::param_name(parameter),
-                    r#in: String::from("query"),
-                    description: String::from("TODO"),
-                    required: true,
-                    explode: false,
-                    schema: ParameterSchema::Integer {
-                      r#type: String::from("integer"),
-                      format: String::from("int64"),
-                    },
-                  })
-                  .collect();
-                parameters
-                },
-                request_body: RequestBody {
-                  // TODO: fixme
-                  required: true,
-                  content: Content {
-                    content_type: ContentType {
-                      schema: Schema::Ref {
-                        r#ref: format!(
-                          "#/components/schemas/{}",
-                          "TODO",
-                          /*controller_fn
-                          .parameters
-                          .iter()
-                          .find(|param| param.is_json_request_body())
-                          .map(|param| param.full_path_inner_type())
-                          .unwrap()*/
-                        ),
-                      },
-                    },
-                  },
-                },
-                description: String::from("TODO"),
-                responses: HashMap::from([(
-                  String::from("200"),
-                  Response::DescriptionOnly {
-                    description: String::from("OK"),
-                  },
-                )]),
-              },
-            )]),
-          );
+        let mut paths = BTreeMap::new();
+
+        for (route, route_handler) in self.collect_routes() {
+          let mut operations = BTreeMap::new();
+
+          for (method, handler) in route_handler.methods.iter() {
+            match self.resolve_handler(handler) {
+              None => tracing::warn!(
+                "unable to find handler {} of {} {}",
+                handler.handler_path.join("::"),
+                method,
+                route
+              ),
+              Some((handler_name, controller_fn)) => {
+                operations.insert(
+                  method.clone(),
+                  self.build_path(&route, &handler_name, controller_fn),
+                );
+              }
+            }
+          }
+
+          if !operations.is_empty() {
+            paths.insert(axum::path_template(&route).0, operations);
+          }
         }
 
         paths
       },
-      components: {
-        let mut components = HashMap::new();
-
-        for (type_name, fields) in self.components.iter() {
-          components.insert(
-            type_name.clone(),
-            ResourceComponent {
-              r#type: if !fields.is_empty() {
-                String::from("object")
-              } else {
-                // TODO: handle other types (e.g. integer)
-                String::from("string")
-              },
-              required: fields
-                .iter()
-                .filter(|field| field.required)
-                .map(|field| field.field_name.clone().unwrap())
-                .collect(),
-              properties: fields
-                .iter()
-                .map(|field| {
-                  (
-                    field.field_name.clone().unwrap(),
-                    rust_type_to_openapi_type(&field.r#type),
-                  )
-                })
-                .collect(),
-            },
-          );
-        }
-
-        ComponentsSchemas {
-          schemas: components,
-        }
+      components: ComponentsSchemas {
+        schemas: self
+          .components
+          .iter()
+          .map(|(name, schema)| (name.clone(), schema.clone()))
+          .collect(),
       },
     }
   }
 
   fn debug(&self) {
-    println!("found routes {:?}", self.routes);
+    tracing::debug!("found routes {:?}", self.collect_routes());
 
-    println!("found functions: {:#?}", self.fn_declarations);
+    tracing::debug!(
+      "found functions: {:?}",
+      self.fn_declarations.keys().collect::<Vec<_>>()
+    );
 
-    println!(
+    tracing::debug!(
       "found structs: {:?}",
       self
         .structs
@@ -447,61 +1905,331 @@ impl AstTraverser {
         .collect::<Vec<_>>()
     );
 
-    println!("type components: {:#?}", self.components);
+    tracing::debug!("type components: {:?}", self.components);
   }
 }
 
-fn run(path: &str) -> Result<String, Box<dyn std::error::Error>> {
-  let mut file = File::open(path).expect("Unable to open file");
+/// A generated OpenAPI document.
+#[derive(Debug)]
+pub struct Document {
+  /// Name of the Cargo target the document was generated for,
+  /// None for the document generated for source files and crate roots.
+  pub name: Option<String>,
+  pub contents: String,
+}
+
+fn run(args: &cli::Args) -> Result<Vec<Document>, Box<dyn std::error::Error>> {
+  let mut root_files = vec![];
+  let mut targets = vec![];
+
+  for input in args.inputs.iter() {
+    if input.join("Cargo.toml").is_file() {
+      targets.extend(cargo::targets(input)?);
+    } else {
+      root_files.push(root_file(input)?);
+    }
+  }
+
+  if !args.packages.is_empty() {
+    targets.retain(|target| args.packages.contains(&target.package));
+
+    if targets.is_empty() {
+      return Err(format!("no targets found for packages {}", args.packages.join(", ")).into());
+    }
+  }
+
+  let mut documents = vec![];
+
+  if !root_files.is_empty() {
+    let mut traverser = AstTraverser::new();
+
+    for root_file in root_files.iter() {
+      traverser.traverse_crate(root_file)?;
+    }
+
+    documents.push(Document {
+      name: None,
+      contents: generate_document(traverser, None, args)?,
+    });
+  }
+
+  // Each target is traversed on its own so types of a service don't show up in the others.
+  for target in targets.iter() {
+    let mut traverser = AstTraverser::new();
+    traverser.traverse_crate(&target.root)?;
 
-  let mut src = String::new();
-  file.read_to_string(&mut src).expect("Unable to read file");
+    documents.push(Document {
+      name: Some(target.document_name()),
+      contents: generate_document(traverser, Some(target), args)?,
+    });
+  }
 
-  let syntax = syn::parse_file(&src).expect("Unable to parse file");
+  if documents.is_empty() {
+    return Err("no targets found".into());
+  }
 
-  let mut traverser = AstTraverser::new();
-  traverser.traverse(syntax);
+  Ok(documents)
+}
 
-  traverser.used_types.push(String::from("RequestBody"));
+fn generate_document(
+  mut traverser: AstTraverser,
+  target: Option<&cargo::Target>,
+  args: &cli::Args,
+) -> Result<String, Box<dyn std::error::Error>> {
+  traverser.collect_used_types();
   traverser.build_type_components();
   traverser.debug();
 
-  println!(
-    "{}",
-    SERDE_YAML_ENUM_TAG_REGEX.replace_all(&serde_yaml::to_string(&traverser.build_resource())?, "")
-  );
-
-  todo!()
-}
-
-fn rust_type_to_openapi_type(rust_type: &str) -> Type {
-  let typ = if rust_type.eq_ignore_ascii_case("string") {
-    "string"
-  } else if rust_type.eq_ignore_ascii_case("i8") || rust_type.eq_ignore_ascii_case("u8") {
-    "byte"
-  } else if rust_type.eq_ignore_ascii_case("i16")
-    || rust_type.eq_ignore_ascii_case("i32")
-    || rust_type.eq_ignore_ascii_case("i64")
-    || rust_type.eq_ignore_ascii_case("i128")
-    || rust_type.eq_ignore_ascii_case("u16")
-    || rust_type.eq_ignore_ascii_case("u32")
-    || rust_type.eq_ignore_ascii_case("u64")
-    || rust_type.eq_ignore_ascii_case("u128")
-  {
-    "integer"
-  } else if rust_type.eq_ignore_ascii_case("f32") || rust_type.eq_ignore_ascii_case("f64") {
-    "number"
-  } else if rust_type.eq_ignore_ascii_case("boolean") {
-    "boolean"
-  } else {
-    return Type::Ref {
-      r#ref: format!("#/components/schemas/{}", rust_type),
-    };
+  let mut resource = traverser.build_resource();
+
+  if let Some(target) = target {
+    resource.info.title = target.name.clone();
+
+    if let Some(version) = &target.version {
+      resource.info.version = version.clone();
+    }
+  }
+
+  if let Some(title) = &args.title {
+    resource.info.title = title.clone();
+  }
+
+  if let Some(version) = &args.api_version {
+    resource.info.version = version.clone();
+  }
+
+  let document = match args.format {
+    cli::Format::Yaml => serde_yaml::to_string(&resource)?,
+    cli::Format::Json => serde_json::to_string_pretty(&resource)?,
+  };
+
+  Ok(document)
+}
+
+/// Writes a single document to `--output` or stdout.
+/// When there are several documents, `--output` is the directory they are written to.
+fn write_documents(
+  args: &cli::Args,
+  documents: Vec<Document>,
+) -> Result<(), Box<dyn std::error::Error>> {
+  match (documents.len(), &args.output) {
+    (1, None) => {
+      let mut stdout = std::io::stdout().lock();
+
+      let result = stdout
+        .write_all(documents[0].contents.as_bytes())
+        .and_then(|()| stdout.write_all(b"\n"))
+        .and_then(|()| stdout.flush());
+
+      match result {
+        // The reader stopped reading, as `swagger x.rs | head` does.
+        Err(err) if err.kind() == std::io::ErrorKind::BrokenPipe => Ok(()),
+        result => result.map_err(|err| format!("unable to write to stdout: {}", err).into()),
+      }
+    }
+    (1, Some(output)) => std::fs::write(output, &documents[0].contents)
+      .map_err(|err| format!("unable to write {}: {}", output.display(), err).into()),
+    (_, None) => Err(
+      format!(
+        "{} documents were generated ({}), select one with --package or write them to a directory with --output",
+        documents.len(),
+        documents
+          .iter()
+          .map(|document| document.name.as_deref().unwrap_or("openapi"))
+          .collect::<Vec<_>>()
+          .join(", ")
+      )
+      .into(),
+    ),
+    (_, Some(dir)) => {
+      std::fs::create_dir_all(dir)
+        .map_err(|err| format!("unable to create {}: {}", dir.display(), err))?;
+
+      let extension = match args.format {
+        cli::Format::Yaml => "yaml",
+        cli::Format::Json => "json",
+      };
+
+      for document in documents.iter() {
+        let path = dir.join(format!(
+          "{}.{}",
+          document.name.as_deref().unwrap_or("openapi"),
+          extension
+        ));
+
+        std::fs::write(&path, &document.contents)
+          .map_err(|err| format!("unable to write {}: {}", path.display(), err))?;
+      }
+
+      Ok(())
+    }
+  }
+}
+
+/// Returns the file that should be parsed for `input`.
+///
+/// `input` may be a source file or the root of a crate, in which case
+/// its `src/main.rs` or `src/lib.rs` is used.
+fn root_file(input: &FsPath) -> Result<PathBuf, Box<dyn std::error::Error>> {
+  if !input.is_dir() {
+    return Ok(input.to_path_buf());
+  }
+
+  ["src/main.rs", "src/lib.rs"]
+    .iter()
+    .map(|file| input.join(file))
+    .find(|file| file.is_file())
+    .ok_or_else(|| {
+      format!(
+        "{} does not contain a src/main.rs or src/lib.rs file",
+        input.display()
+      )
+      .into()
+    })
+}
+
+/// T in a Json<T> extractor of the handler and whether the body is required,
+/// Option<Json<T>> accepts requests without a body.
+fn request_body_type(controller_fn: &ItemFn) -> Option<(&syn::Type, bool)> {
+  controller_fn.sig.inputs.iter().find_map(|arg| {
+    if let Some(body_type) = ast::extractor_type(arg, "Json") {
+      return Some((body_type, true));
+    }
+
+    match ast::extractor_type(arg, "Option") {
+      Some(option_type) => {
+        ast::generic_type_argument(option_type, "Json").map(|body_type| (body_type, false))
+      }
+      None => None,
+    }
+  })
+}
+
+/// Value of Default::default() for primitive types: 0, false, "", []
+fn type_default_value(ty: &syn::Type) -> Option<serde_json::Value> {
+  if ast::generic_type_argument(ty, "Vec").is_some() {
+    return Some(serde_json::Value::Array(vec![]));
+  }
+
+  let type_name = ast::type_name(ty)?;
+
+  match primitive_schema(&type_name)?.r#type.as_deref()? {
+    "integer" => Some(serde_json::Value::from(0)),
+    "number" => Some(serde_json::Value::from(0.0)),
+    "boolean" => Some(serde_json::Value::from(false)),
+    "string" if type_name == "String" => Some(serde_json::Value::from("")),
+    _ => None,
+  }
+}
+
+/// The field that newtype structs and #[serde(transparent)] structs are serialized as.
+fn newtype_field(struct_: &ItemStruct) -> Option<&syn::Field> {
+  let mut fields = struct_
+    .fields
+    .iter()
+    .filter(|field| ast::type_name(&field.ty).as_deref() != Some("PhantomData"));
+
+  let is_newtype = match &struct_.fields {
+    syn::Fields::Unnamed(fields) => fields.unnamed.len() == 1,
+    syn::Fields::Named(_) => serde_attr::is_transparent(&struct_.attrs),
+    syn::Fields::Unit => false,
   };
 
-  Type::Primitive {
-    r#type: String::from(typ),
+  match (fields.next(), fields.next()) {
+    (Some(field), None) if is_newtype => Some(field),
+    _ => None,
+  }
+}
+
+/// Fields of a tuple struct or variant without #[serde(skip)], they are serialized as an array.
+fn serialized_tuple_fields(fields: &syn::FieldsUnnamed) -> impl Iterator<Item = &syn::Field> {
+  fields
+    .unnamed
+    .iter()
+    .enumerate()
+    .filter_map(|(index, field)| {
+      let attrs = serde_attr::field_attrs(
+        &field.attrs,
+        &index.to_string(),
+        serde_attr::RenameAll::default(),
+      );
+
+      (!attrs.skip_serializing).then_some(field)
+    })
+}
+
+/// T when `ty` is a single identifier that could be a generic parameter.
+fn type_parameter(ty: &syn::Type) -> Option<String> {
+  match ty {
+    syn::Type::Path(type_path) if type_path.qself.is_none() => {
+      type_path.path.get_ident().map(|ident| ident.to_string())
+    }
+    _ => None,
+  }
+}
+
+/// Binds the type parameters of a declaration to the arguments of a use: struct Page<T> used as Page<User> -> T: User
+fn bind_type_parameters(declaration: &syn::Generics, arguments: Vec<TypeArgument>) -> Generics {
+  declaration
+    .type_params()
+    .map(|param| param.ident.to_string())
+    .zip(arguments)
+    .collect()
+}
+
+/// Schema of a primitive Rust type, None for other types.
+///
+/// u8 -> {"type": "integer", "format": "int32", "minimum": 0, "maximum": 255}
+fn primitive_schema(rust_type: &str) -> Option<Schema> {
+  // NonZeroU32 is a u32 that isn't 0.
+  if let Some(integer_type) = rust_type.strip_prefix("NonZero") {
+    let mut schema = primitive_schema(&integer_type.to_ascii_lowercase())?;
+
+    if integer_type.starts_with('U') {
+      schema.minimum = Some(serde_json::Number::from(1));
+    }
+
+    return Some(schema);
   }
+
+  let integer = |format: Option<&str>, minimum: Option<i64>, maximum: Option<u64>| Schema {
+    format: format.map(String::from),
+    minimum: minimum.map(serde_json::Number::from),
+    maximum: maximum.map(serde_json::Number::from),
+    ..Schema::primitive("integer")
+  };
+
+  let number = |format: &str| Schema {
+    format: Some(String::from(format)),
+    ..Schema::primitive("number")
+  };
+
+  let schema = match rust_type {
+    "String" | "str" | "Uuid" => Schema::primitive("string"),
+    "char" => Schema {
+      min_length: Some(1),
+      max_length: Some(1),
+      ..Schema::primitive("string")
+    },
+    "bool" => Schema::primitive("boolean"),
+    "f32" => number("float"),
+    "f64" => number("double"),
+    "i8" => integer(Some("int32"), Some(i8::MIN.into()), Some(i8::MAX as u64)),
+    "i16" => integer(Some("int32"), Some(i16::MIN.into()), Some(i16::MAX as u64)),
+    "i32" => integer(Some("int32"), Some(i32::MIN.into()), Some(i32::MAX as u64)),
+    "i64" | "isize" => integer(Some("int64"), Some(i64::MIN), Some(i64::MAX as u64)),
+    "u8" => integer(Some("int32"), Some(0), Some(u8::MAX.into())),
+    "u16" => integer(Some("int32"), Some(0), Some(u16::MAX.into())),
+    "u32" => integer(Some("int64"), Some(0), Some(u32::MAX.into())),
+    // Values above i64::MAX don't fit in int64, it is still the closest format.
+    "u64" | "usize" => integer(Some("int64"), Some(0), Some(u64::MAX)),
+    // No format holds 128 bits integers.
+    "i128" => integer(None, None, None),
+    "u128" => integer(None, Some(0), None),
+    _ => return None,
+  };
+
+  Some(schema)
 }
 
 #[derive(Debug, Serialize)]
@@ -510,7 +2238,7 @@ struct Resource {
   pub openapi: String,
   pub info: Info,
   pub servers: Vec<Server>,
-  pub paths: HashMap<String, HashMap<String, Path>>,
+  pub paths: BTreeMap<String, BTreeMap<String, Path>>,
   pub components: ComponentsSchemas,
 }
 
@@ -531,25 +2259,26 @@ struct Server {
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ComponentsSchemas {
-  pub schemas: HashMap<String, ResourceComponent>,
-}
-
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ResourceComponent {
-  pub r#type: String,
-  pub required: Vec<String>,
-  pub properties: HashMap<String, Type>,
+  pub schemas: BTreeMap<String, Schema>,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 struct Path {
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub tags: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub summary: Option<String>,
-  pub description: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub operation_id: Option<String>,
   pub parameters: Vec<Parameter>,
-  pub request_body: RequestBody,
-  pub responses: HashMap<String, Response>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub request_body: Option<RequestBody>,
+  pub responses: BTreeMap<String, Response>,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub deprecated: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -557,34 +2286,53 @@ struct Path {
 struct Parameter {
   pub name: String,
   pub r#in: String,
-  pub description: String,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
   pub required: bool,
   // TODO: what does this even mean?
   pub explode: bool,
-  pub schema: ParameterSchema,
+  pub schema: Schema,
 }
 
 #[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-enum ParameterSchema {
-  Enum {
-    r#type: String,
-    default: String,
-    r#enum: Vec<String>,
-  },
-  Array {
-    r#type: String,
-    items: Type,
+#[serde(untagged)]
+enum Response {
+  DescriptionOnly {
+    description: String,
   },
-  Primitive {
-    r#type: String,
+  Content {
+    description: String,
+    content: BTreeMap<String, ContentType>,
   },
 }
 
-#[derive(Debug, Serialize)]
-#[serde(rename_all = "camelCase")]
-enum Response {
-  DescriptionOnly { description: String },
+impl Response {
+  /// Adds examples to the content of the response, responses without content get a JSON content.
+  fn set_examples(&mut self, examples: Vec<docs::Example>) {
+    if let Response::DescriptionOnly { description } = self {
+      *self = Response::Content {
+        description: std::mem::take(description),
+        content: BTreeMap::from([(
+          String::from("application/json"),
+          ContentType::new(Schema::default()),
+        )]),
+      };
+    }
+
+    if let Response::Content { content, .. } = self {
+      if let Some(content_type) = content.values_mut().next() {
+        content_type.set_examples(examples);
+      }
+    }
+  }
+
+  fn set_description(&mut self, new_description: String) {
+    match self {
+      Response::DescriptionOnly { description } | Response::Content { description, .. } => {
+        *description = new_description
+      }
+    }
+  }
 }
 
 #[derive(Debug, Serialize)]
@@ -605,29 +2353,156 @@ struct Content {
 #[serde(rename_all = "camelCase")]
 struct ContentType {
   pub schema: Schema,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub example: Option<serde_json::Value>,
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  pub examples: BTreeMap<String, ExampleObject>,
+}
+
+impl ContentType {
+  fn new(schema: Schema) -> Self {
+    Self {
+      schema,
+      example: None,
+      examples: BTreeMap::new(),
+    }
+  }
+
+  /// A single example is the `example`, several examples are named after the text before their block.
+  fn set_examples(&mut self, examples: Vec<docs::Example>) {
+    if examples.len() == 1 {
+      self.example = examples.into_iter().next().map(|example| example.value);
+      return;
+    }
+
+    for (i, example) in examples.into_iter().enumerate() {
+      let name = example.name.unwrap_or_else(|| format!("example{}", i + 1));
+
+      self.examples.insert(
+        name,
+        ExampleObject {
+          value: example.value,
+        },
+      );
+    }
+  }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
-enum Schema {
-  Properties {
-    r#type: String,
-    properties: Option<HashMap<String, Type>>,
-  },
-  Ref {
-    #[serde(rename = "$ref")]
-    r#ref: String,
-  },
+struct ExampleObject {
+  pub value: serde_json::Value,
 }
 
-#[derive(Debug, Serialize)]
+/// A schema of the document, only the fields that are set are serialized.
+///
+/// Schema::default() is {}, the schema of any value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
-pub enum Type {
-  Primitive {
-    r#type: String,
-  },
-  Ref {
-    #[serde(rename = "$ref")]
-    r#ref: String,
-  },
+struct Schema {
+  #[serde(rename = "$ref", skip_serializing_if = "Option::is_none")]
+  pub r#ref: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub r#type: Option<String>,
+  /// int32, int64, float or double.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub format: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub description: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub default: Option<serde_json::Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub example: Option<serde_json::Value>,
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub nullable: bool,
+  /// Only in responses: #[serde(skip_deserializing)]
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub read_only: bool,
+  /// Only in requests: #[serde(skip_serializing)]
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub write_only: bool,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub r#enum: Vec<serde_json::Value>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub minimum: Option<serde_json::Number>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub maximum: Option<serde_json::Number>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub min_length: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_length: Option<u64>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub required: Vec<String>,
+  #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+  pub properties: BTreeMap<String, Schema>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub additional_properties: Option<AdditionalProperties>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub items: Option<Box<Schema>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub min_items: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub max_items: Option<u64>,
+  /// HashSet<T> and BTreeSet<T>
+  #[serde(skip_serializing_if = "std::ops::Not::not")]
+  pub unique_items: bool,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub one_of: Vec<Schema>,
+  #[serde(skip_serializing_if = "Vec::is_empty")]
+  pub all_of: Vec<Schema>,
+}
+
+/// additionalProperties of an object schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+enum AdditionalProperties {
+  /// false for #[serde(deny_unknown_fields)]
+  Allowed(bool),
+  /// The schema of the values of a map.
+  Schema(Box<Schema>),
+}
+
+impl Schema {
+  /// {"type": "integer"}
+  fn primitive(r#type: &str) -> Self {
+    Self {
+      r#type: Some(String::from(r#type)),
+      ..Self::default()
+    }
+  }
+
+  /// {"$ref": "#/components/schemas/User"}
+  fn reference(component_name: &str) -> Self {
+    Self {
+      r#ref: Some(format!("#/components/schemas/{}", component_name)),
+      ..Self::default()
+    }
+  }
+
+  fn object() -> Self {
+    Self::primitive("object")
+  }
+
+  fn array(items: Schema) -> Self {
+    Self {
+      items: Some(Box::new(items)),
+      ..Self::primitive("array")
+    }
+  }
+
+  /// {"type": "string", "enum": ["Active", "Inactive"]}
+  fn string_enum(values: Vec<String>) -> Self {
+    Self {
+      r#enum: values.into_iter().map(serde_json::Value::from).collect(),
+      ..Self::primitive("string")
+    }
+  }
+
+  fn add_property(&mut self, name: String, property: Schema, required: bool) {
+    if required {
+      self.required.push(name.clone());
+    }
+
+    self.properties.insert(name, property);
+  }
 }
@@ -0,0 +1,411 @@
+use syn::{
+  parse::ParseStream,
+  visit::{self, Visit},
+  Expr, ExprCall, ExprReturn, ExprTuple, Lit, Type,
+};
+
+use crate::{ast, axum};
+
+/// Body of a response, found from the type that is converted into the response.
+#[derive(Debug, Clone)]
+pub enum Body<'a> {
+  /// (), StatusCode
+  Empty,
+  /// T in Json<T>
+  Json(&'a Type),
+  /// ErrorBody in Json(ErrorBody { .. })
+  JsonStruct(&'a syn::Path),
+  /// The keys and types of json!({ "error": message, "code": 404 }): [(error, String), (code, i64)]
+  JsonObject(Vec<(String, String)>),
+  /// Html<T>
+  Html,
+  /// String, &'static str
+  Text,
+  /// impl IntoResponse, Response and types that aren't known.
+  Unknown,
+}
+
+/// A response a handler may return.
+#[derive(Debug)]
+pub struct HandlerResponse<'a> {
+  pub status: u16,
+  pub body: Body<'a>,
+}
+
+/// Returns the responses of a handler, inferred from its return type and the status codes
+/// found in the expressions its body returns. Aliases of the return type are expected to be expanded.
+///
+/// Json<T> -> 200 with T
+/// (StatusCode, Json<T>) returning (StatusCode::CREATED, Json(user)) -> 201 with T
+/// Result<Json<T>, StatusCode> returning Err(StatusCode::NOT_FOUND) -> 200 with T and 404
+pub fn handler_responses<'a>(
+  output: Option<&'a Type>,
+  block: &'a syn::Block,
+) -> Vec<HandlerResponse<'a>> {
+  let returned = returned_exprs(block);
+
+  let (ok_type, err_type) = match output.and_then(result_types) {
+    None => return responses(output, &returned),
+    Some(types) => types,
+  };
+
+  let mut handler_responses = responses(Some(ok_type), &call_args(&returned, "Ok"));
+
+  // Errors are usually returned with `?`, so every error status code of the body is used.
+  if is_status_code(err_type) {
+    let mut visitor = StatusCodeVisitor::default();
+    visitor.visit_block(block);
+
+    for status in visitor.codes.into_iter().filter(|code| *code >= 400) {
+      handler_responses.push(HandlerResponse {
+        status,
+        body: body(last_element(err_type)),
+      });
+    }
+  }
+
+  handler_responses
+}
+
+/// E in Result<T, E> when it isn't a StatusCode, its responses come from `impl IntoResponse for E`.
+pub fn error_type(output: &Type) -> Option<&Type> {
+  result_types(output)
+    .map(|(_, err_type)| err_type)
+    .filter(|err_type| !is_status_code(err_type))
+}
+
+/// Returns the responses of `fn into_response(self) -> Response` with `block` as its body.
+///
+/// Error types usually match on self and use a StatusCode in each arm:
+///
+/// AppError::NotFound => (StatusCode::NOT_FOUND, "user not found") -> 404
+///
+/// Every 4xx and 5xx status code gets a response, they all have the body built in `into_response`.
+pub fn into_response_responses(block: &syn::Block) -> Vec<HandlerResponse<'_>> {
+  let mut status_codes = StatusCodeVisitor::default();
+  status_codes.visit_block(block);
+
+  let mut error_body = ErrorBodyVisitor::default();
+  error_body.visit_block(block);
+
+  let body = match (error_body.json, error_body.text) {
+    (Some(body), _) => body,
+    (None, true) => Body::Text,
+    (None, false) => Body::Empty,
+  };
+
+  status_codes
+    .codes
+    .into_iter()
+    .filter(|code| *code >= 400)
+    .map(|status| HandlerResponse {
+      status,
+      body: body.clone(),
+    })
+    .collect()
+}
+
+/// Responses of the type `ty` returned by `exprs`.
+fn responses<'a>(ty: Option<&'a Type>, exprs: &[&Expr]) -> Vec<HandlerResponse<'a>> {
+  let ty = match ty {
+    None => {
+      return vec![HandlerResponse {
+        status: 200,
+        body: Body::Empty,
+      }]
+    }
+    Some(ty) => ty,
+  };
+
+  let mut codes = vec![];
+
+  if is_status_code(ty) {
+    for expr in exprs.iter() {
+      let code = match expr {
+        // (StatusCode::CREATED, Json(user))
+        Expr::Tuple(tuple) => tuple.elems.first().and_then(status_code),
+        expr => status_code(expr),
+      };
+
+      match code {
+        Some(code) if !codes.contains(&code) => codes.push(code),
+        _ => {}
+      }
+    }
+  }
+
+  if codes.is_empty() {
+    codes.push(200);
+  }
+
+  codes
+    .into_iter()
+    .map(|status| HandlerResponse {
+      status,
+      body: body(last_element(ty)),
+    })
+    .collect()
+}
+
+/// Body of the response the type `ty` is converted into.
+fn body(ty: &Type) -> Body<'_> {
+  match ty {
+    Type::Tuple(tuple) if tuple.elems.is_empty() => Body::Empty,
+    Type::Reference(reference) => body(&reference.elem),
+    Type::Paren(paren) => body(&paren.elem),
+    _ => {
+      if let Some(body_type) = ast::generic_type_argument(ty, "Json") {
+        return Body::Json(body_type);
+      }
+
+      match ast::type_name(ty).as_deref() {
+        Some("StatusCode") => Body::Empty,
+        Some("Html") => Body::Html,
+        Some("String") | Some("str") => Body::Text,
+        _ => Body::Unknown,
+      }
+    }
+  }
+}
+
+/// T and E in Result<T, E>
+fn result_types(ty: &Type) -> Option<(&Type, &Type)> {
+  let segment = match ty {
+    Type::Path(type_path) => type_path.path.segments.last()?,
+    _ => return None,
+  };
+
+  if segment.ident != "Result" {
+    return None;
+  }
+
+  let mut types = match &segment.arguments {
+    syn::PathArguments::AngleBracketed(args) => args.args.iter().filter_map(|arg| match arg {
+      syn::GenericArgument::Type(ty) => Some(ty),
+      _ => None,
+    }),
+    _ => return None,
+  };
+
+  Some((types.next()?, types.next()?))
+}
+
+/// Returns true for StatusCode and tuples whose first element is a StatusCode: (StatusCode, Json<T>)
+fn is_status_code(ty: &Type) -> bool {
+  let ty = match ty {
+    Type::Tuple(tuple) => match tuple.elems.first() {
+      None => return false,
+      Some(first) => first,
+    },
+    ty => ty,
+  };
+
+  ast::type_name(ty).as_deref() == Some("StatusCode")
+}
+
+/// The body of a response tuple is its last element: Json<T> in (StatusCode, HeaderMap, Json<T>)
+fn last_element(ty: &Type) -> &Type {
+  match ty {
+    Type::Tuple(tuple) => tuple.elems.last().unwrap_or(ty),
+    ty => ty,
+  }
+}
+
+/// 201 in StatusCode::CREATED or axum::http::StatusCode::CREATED
+fn status_code(expr: &Expr) -> Option<u16> {
+  match expr {
+    Expr::Path(path) => {
+      let segments = ast::path_segments(&path.path);
+
+      match segments.as_slice() {
+        [.., status_code, constant] if status_code == "StatusCode" => axum::status_code(constant),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+/// x in every Ok(x) of `exprs`
+fn call_args<'a>(exprs: &[&'a Expr], func: &str) -> Vec<&'a Expr> {
+  exprs
+    .iter()
+    .filter_map(|expr| match expr {
+      Expr::Call(call) if call.args.len() == 1 => match &*call.func {
+        Expr::Path(path) if path.path.is_ident(func) => call.args.first(),
+        _ => None,
+      },
+      _ => None,
+    })
+    .collect()
+}
+
+/// Expressions the function with `block` as its body may return: its tail expression,
+/// the branches of a tail `if` or `match` and the values of `return` expressions.
+fn returned_exprs(block: &syn::Block) -> Vec<&Expr> {
+  let mut visitor = ReturnVisitor::default();
+  visitor.visit_block(block);
+
+  let mut exprs = visitor.exprs;
+
+  if let Some(tail) = ast::tail_expr(block) {
+    tail_exprs(tail, &mut exprs);
+  }
+
+  exprs
+}
+
+fn tail_exprs<'a>(expr: &'a Expr, exprs: &mut Vec<&'a Expr>) {
+  match expr {
+    Expr::If(if_) => {
+      if let Some(tail) = ast::tail_expr(&if_.then_branch) {
+        tail_exprs(tail, exprs);
+      }
+
+      if let Some((_, else_branch)) = &if_.else_branch {
+        tail_exprs(else_branch, exprs);
+      }
+    }
+    Expr::Match(match_) => {
+      for arm in match_.arms.iter() {
+        tail_exprs(&arm.body, exprs);
+      }
+    }
+    Expr::Block(block) => {
+      if let Some(tail) = ast::tail_expr(&block.block) {
+        tail_exprs(tail, exprs);
+      }
+    }
+    Expr::Paren(paren) => tail_exprs(&paren.expr, exprs),
+    expr => exprs.push(expr),
+  }
+}
+
+/// Values of the `return` expressions of a function, closures and nested items are skipped.
+#[derive(Default)]
+struct ReturnVisitor<'ast> {
+  exprs: Vec<&'ast Expr>,
+}
+
+impl<'ast> Visit<'ast> for ReturnVisitor<'ast> {
+  fn visit_expr_return(&mut self, expr_return: &'ast ExprReturn) {
+    if let Some(expr) = &expr_return.expr {
+      tail_exprs(expr, &mut self.exprs);
+    }
+
+    visit::visit_expr_return(self, expr_return);
+  }
+
+  fn visit_expr_closure(&mut self, _: &'ast syn::ExprClosure) {}
+
+  fn visit_expr_async(&mut self, _: &'ast syn::ExprAsync) {}
+
+  fn visit_item(&mut self, _: &'ast syn::Item) {}
+}
+
+/// Codes of every StatusCode constant used in a function, in the order they appear.
+#[derive(Default)]
+struct StatusCodeVisitor {
+  codes: Vec<u16>,
+}
+
+impl<'ast> Visit<'ast> for StatusCodeVisitor {
+  fn visit_expr(&mut self, expr: &'ast Expr) {
+    match status_code(expr) {
+      Some(code) if !self.codes.contains(&code) => self.codes.push(code),
+      _ => visit::visit_expr(self, expr),
+    }
+  }
+}
+
+/// Body of the response built in `into_response`.
+#[derive(Default)]
+struct ErrorBodyVisitor<'ast> {
+  /// Json(ErrorBody { .. }) or Json(json!({ .. }))
+  json: Option<Body<'ast>>,
+  /// (status, "message") or (status, message.to_string())
+  text: bool,
+}
+
+impl<'ast> Visit<'ast> for ErrorBodyVisitor<'ast> {
+  fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+    let is_json = match &*call.func {
+      Expr::Path(path) => path
+        .path
+        .segments
+        .last()
+        .map(|segment| segment.ident == "Json")
+        .unwrap_or(false),
+      _ => false,
+    };
+
+    if is_json && self.json.is_none() {
+      self.json = match call.args.first() {
+        Some(Expr::Struct(expr_struct)) => Some(Body::JsonStruct(&expr_struct.path)),
+        Some(Expr::Macro(expr_macro)) if expr_macro.mac.path.is_ident("json") => Some(
+          Body::JsonObject(json_object_keys(&expr_macro.mac).unwrap_or_default()),
+        ),
+        _ => Some(Body::JsonObject(vec![])),
+      };
+    }
+
+    visit::visit_expr_call(self, call);
+  }
+
+  fn visit_expr_tuple(&mut self, tuple: &'ast ExprTuple) {
+    let is_text = match tuple.elems.last() {
+      Some(Expr::Lit(lit)) => matches!(lit.lit, Lit::Str(_)),
+      Some(Expr::Macro(expr_macro)) => expr_macro.mac.path.is_ident("format"),
+      Some(Expr::MethodCall(method_call)) => method_call.method == "to_string",
+      _ => false,
+    };
+
+    self.text = self.text || (tuple.elems.len() > 1 && is_text);
+
+    visit::visit_expr_tuple(self, tuple);
+  }
+}
+
+/// Keys of the object in json!({ "error": message, "code": 404 }) and the Rust type of their value,
+/// values that aren't literals are expected to be messages: [(error, String), (code, i64)]
+fn json_object_keys(mac: &syn::Macro) -> syn::Result<Vec<(String, String)>> {
+  let parser = |input: ParseStream| {
+    let content;
+    syn::braced!(content in input);
+
+    let mut keys = vec![];
+
+    while !content.is_empty() {
+      let key: syn::LitStr = content.parse()?;
+      content.parse::<syn::Token![:]>()?;
+
+      let value_type = if content.peek(syn::LitInt) {
+        "i64"
+      } else if content.peek(syn::LitFloat) {
+        "f64"
+      } else if content.peek(syn::LitBool) {
+        "bool"
+      } else {
+        "String"
+      };
+
+      // Skips the value.
+      while !content.is_empty() && !content.peek(syn::Token![,]) {
+        content.step(|cursor| match cursor.token_tree() {
+          Some((_, rest)) => Ok(((), rest)),
+          None => Err(cursor.error("unexpected end of input")),
+        })?;
+      }
+
+      if !content.is_empty() {
+        content.parse::<syn::Token![,]>()?;
+      }
+
+      keys.push((key.value(), String::from(value_type)));
+    }
+
+    Ok(keys)
+  };
+
+  mac.parse_body_with(parser)
+}
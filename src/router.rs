@@ -0,0 +1,300 @@
+use std::collections::{HashMap, HashSet};
+
+use syn::{
+  visit::{self, Visit},
+  Expr, ExprMethodCall, Local, Pat,
+};
+
+use crate::{ast, Handler, RouteHandler};
+
+/// Functions of axum::routing that route a HTTP method to a handler: get(handler)
+const METHODS: [&str; 8] = [
+  "get", "post", "put", "delete", "patch", "head", "options", "trace",
+];
+
+/// Something a function does to the Router it builds.
+#[derive(Debug)]
+pub enum RouterEntry {
+  /// .route("/path", get(handler))
+  Route { path: String, handler: RouteHandler },
+  /// .nest("/prefix", users_router()) or .merge(users_router())
+  ///
+  /// The routes of the function `router` are added with `prefix`.
+  Nest {
+    prefix: String,
+    /// Path to the function as written in the source code.
+    router: Vec<String>,
+    /// Module the function was called from, the path is resolved from it.
+    module: Vec<String>,
+  },
+}
+
+/// Returns what the function with `block` as its body does to Routers.
+///
+/// Every expression is visited, so routes are found in `Router::new().route(..).route(..)`
+/// chains, `let` statements, nested blocks, `if` branches and tail expressions.
+pub fn router_entries(block: &syn::Block, module: &[String]) -> Vec<RouterEntry> {
+  let mut nested_variables = NestedVariables::default();
+  nested_variables.visit_block(block);
+
+  let mut visitor = RouteVisitor {
+    module,
+    prefix: String::new(),
+    nested_variables: nested_variables.names,
+    bindings: HashMap::new(),
+    entries: vec![],
+  };
+  visitor.visit_block(block);
+
+  visitor.entries
+}
+
+/// Joins the prefix of a nested Router with one of its paths.
+///
+/// ("/api", "/users") -> "/api/users", ("/api", "/") -> "/api"
+pub fn join_paths(prefix: &str, path: &str) -> String {
+  let prefix = prefix.trim_end_matches('/');
+
+  if prefix.is_empty() {
+    path.to_owned()
+  } else if path == "/" || path.is_empty() {
+    prefix.to_owned()
+  } else {
+    format!("{}/{}", prefix, path.trim_start_matches('/'))
+  }
+}
+
+/// Names of the variables passed to .nest() and .merge():
+///
+/// let users = Router::new().route(..);
+/// app.nest("/users", users)
+/// app.nest("/users", users.layer(..))
+#[derive(Default)]
+struct NestedVariables {
+  names: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for NestedVariables {
+  fn visit_expr_method_call(&mut self, method_call: &'ast ExprMethodCall) {
+    let router = nested_router_arg(method_call).map(|router| match router {
+      Expr::MethodCall(router) => innermost_receiver(router),
+      router => router,
+    });
+
+    if let Some(Expr::Path(path)) = router {
+      if let Some(ident) = path.path.get_ident() {
+        self.names.insert(ident.to_string());
+      }
+    }
+
+    visit::visit_expr_method_call(self, method_call);
+  }
+}
+
+struct RouteVisitor<'a> {
+  module: &'a [String],
+  /// Prefix of the nested Router being visited.
+  prefix: String,
+  nested_variables: HashSet<String>,
+  /// Routers assigned to nested variables, they are visited when they are nested.
+  bindings: HashMap<String, Expr>,
+  entries: Vec<RouterEntry>,
+}
+
+impl<'a> RouteVisitor<'a> {
+  // Calling Router.route("/path", method(controller))
+  fn add_route(&mut self, method_call: &ExprMethodCall) {
+    let route = match &method_call.args[0] {
+      Expr::Lit(lit) => match &lit.lit {
+        syn::Lit::Str(path) => path.value(),
+        _ => return,
+      },
+      _ => return,
+    };
+
+    let mut handler = RouteHandler::default();
+    self.add_method_router(&method_call.args[1], &mut handler);
+
+    if !handler.methods.is_empty() {
+      self.entries.push(RouterEntry::Route {
+        path: join_paths(&self.prefix, &route),
+        handler,
+      });
+    }
+  }
+
+  /// Adds the handlers of a MethodRouter: get(list).post(create).delete(remove)
+  fn add_method_router(&self, expr: &Expr, route_handler: &mut RouteHandler) {
+    let (method, handler, receiver) = match expr {
+      // get(handler)
+      Expr::Call(call) => match &*call.func {
+        Expr::Path(path) => (
+          path.path.segments.last().unwrap().ident.to_string(),
+          call.args.last(),
+          None,
+        ),
+        _ => return,
+      },
+      // get(list).post(create)
+      Expr::MethodCall(method_call) => (
+        method_call.method.to_string(),
+        method_call.args.last(),
+        Some(&*method_call.receiver),
+      ),
+      _ => return,
+    };
+
+    if let Some(receiver) = receiver {
+      self.add_method_router(receiver, route_handler);
+    }
+
+    // Calls such as .layer(..) are skipped.
+    if !METHODS.contains(&method.as_str()) {
+      return;
+    }
+
+    if let Some(Expr::Path(path)) = handler {
+      route_handler.methods.insert(
+        method,
+        Handler {
+          handler_path: ast::path_segments(&path.path),
+          module: self.module.to_vec(),
+        },
+      );
+    }
+  }
+
+  /// Visits the Router passed to .nest(prefix, router) or .merge(router).
+  fn visit_nested_router(&mut self, nest_prefix: &str, router: &Expr) {
+    let prefix = join_paths(&self.prefix, nest_prefix);
+
+    match router {
+      // .nest("/users", users_router())
+      Expr::Call(call) => match &*call.func {
+        Expr::Path(path) => self.entries.push(RouterEntry::Nest {
+          prefix,
+          router: ast::path_segments(&path.path),
+          module: self.module.to_vec(),
+        }),
+        _ => self.visit_with_prefix(prefix, router),
+      },
+      // .nest("/users", users), users is nested like the expression it is bound to.
+      Expr::Path(path) => {
+        let binding = path
+          .path
+          .get_ident()
+          .and_then(|ident| self.bindings.get(&ident.to_string()))
+          .cloned();
+
+        if let Some(binding) = binding {
+          self.visit_nested_router(nest_prefix, &binding);
+        }
+      }
+      // .nest("/users", users_router().layer(..)), the chain is nested like its receiver,
+      // the calls in the chain may still add routes.
+      Expr::MethodCall(method_call) => {
+        let receiver = innermost_receiver(method_call);
+        if !is_router_constructor(receiver) {
+          self.visit_nested_router(nest_prefix, receiver);
+        }
+
+        self.visit_with_prefix(prefix, router);
+      }
+      _ => self.visit_with_prefix(prefix, router),
+    }
+  }
+
+  fn visit_with_prefix(&mut self, prefix: String, expr: &Expr) {
+    let previous_prefix = std::mem::replace(&mut self.prefix, prefix);
+    self.visit_expr(expr);
+    self.prefix = previous_prefix;
+  }
+}
+
+impl<'a, 'ast> Visit<'ast> for RouteVisitor<'a> {
+  fn visit_local(&mut self, local: &'ast Local) {
+    // Nested variables are visited with the prefix they are nested with.
+    if let (Pat::Ident(pat_ident), Some((_, init))) = (&local.pat, &local.init) {
+      let name = pat_ident.ident.to_string();
+      if self.nested_variables.contains(&name) {
+        self.bindings.insert(name, *init.clone());
+        return;
+      }
+    }
+
+    visit::visit_local(self, local);
+  }
+
+  fn visit_expr_method_call(&mut self, method_call: &'ast ExprMethodCall) {
+    if method_call.method == "route" && method_call.args.len() == 2 {
+      self.add_route(method_call);
+    }
+
+    let nested_router = nested_router_arg(method_call);
+
+    // The receiver may register more routes: Router::new().route(..).route(..)
+    self.visit_expr(&method_call.receiver);
+
+    for arg in method_call.args.iter() {
+      match nested_router {
+        Some(router) if std::ptr::eq(router, arg) => {
+          let prefix = nest_prefix(method_call).unwrap_or_default();
+          self.visit_nested_router(&prefix, router);
+        }
+        _ => self.visit_expr(arg),
+      }
+    }
+  }
+}
+
+/// users in .nest("/users", users) and .merge(users)
+fn nested_router_arg(method_call: &ExprMethodCall) -> Option<&Expr> {
+  match (
+    method_call.method.to_string().as_str(),
+    method_call.args.len(),
+  ) {
+    ("nest", 2) if nest_prefix(method_call).is_some() => method_call.args.last(),
+    ("merge", 1) => method_call.args.first(),
+    _ => None,
+  }
+}
+
+/// users_router() in users_router().layer(..).with_state(..)
+fn innermost_receiver(method_call: &ExprMethodCall) -> &Expr {
+  match &*method_call.receiver {
+    Expr::MethodCall(receiver) => innermost_receiver(receiver),
+    receiver => receiver,
+  }
+}
+
+/// Router::new() and Router::default() start a router in place, its routes are in the chain.
+fn is_router_constructor(expr: &Expr) -> bool {
+  match expr {
+    Expr::Call(call) => match &*call.func {
+      Expr::Path(path) => {
+        let segments = ast::path_segments(&path.path);
+        matches!(
+          segments.iter().map(String::as_str).collect::<Vec<_>>()[..],
+          [.., "Router", "new" | "default"]
+        )
+      }
+      _ => false,
+    },
+    _ => false,
+  }
+}
+
+/// "/users" in .nest("/users", users)
+fn nest_prefix(method_call: &ExprMethodCall) -> Option<String> {
+  if method_call.method != "nest" {
+    return None;
+  }
+
+  match method_call.args.first() {
+    Some(Expr::Lit(lit)) => match &lit.lit {
+      syn::Lit::Str(prefix) => Some(prefix.value()),
+      _ => None,
+    },
+    _ => None,
+  }
+}
@@ -0,0 +1,185 @@
+use std::collections::{HashMap, HashSet};
+
+use syn::UseTree;
+
+/// Items and imports declared in a module.
+#[derive(Debug, Default)]
+pub struct Scope {
+  /// Names of the structs, functions and modules declared in the module.
+  items: HashSet<String>,
+  /// use a::b as c; -> c: [a, b]
+  imports: HashMap<String, Vec<String>>,
+  /// use a::b::*; -> [a, b]
+  glob_imports: Vec<Vec<String>>,
+}
+
+/// What `name` resolves to in a module, keyed by (module, name), for a single lookup.
+///
+/// The entry is None while the name is being resolved, so glob imports that form a cycle
+/// stop instead of being searched again, and each module is searched once per name.
+type Resolutions = HashMap<(String, String), Option<Vec<String>>>;
+
+/// The scopes of every module in the crate, keyed by the module path (e.g. crate::handlers::users).
+///
+/// Used to resolve the paths found in the source code to the fully qualified path
+/// of the item they refer to, the same way rustc would.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+  scopes: HashMap<String, Scope>,
+}
+
+impl SymbolTable {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Registers an item named `name` declared in `module`.
+  pub fn add_item(&mut self, module: &[String], name: String) {
+    self.scope_mut(module).items.insert(name);
+  }
+
+  /// Registers the names imported by `use` in `module`.
+  pub fn add_use(&mut self, module: &[String], tree: &UseTree) {
+    let mut imports = vec![];
+    let mut glob_imports = vec![];
+    flatten_use_tree(vec![], tree, &mut imports, &mut glob_imports);
+
+    let scope = self.scope_mut(module);
+    scope.imports.extend(imports);
+    scope.glob_imports.extend(glob_imports);
+  }
+
+  fn scope_mut(&mut self, module: &[String]) -> &mut Scope {
+    self.scopes.entry(module.join("::")).or_default()
+  }
+
+  /// Returns the fully qualified path of the item `path` refers to when used in `module`.
+  ///
+  /// Returns None for items that weren't declared in the crate (e.g. std::string::String).
+  pub fn resolve(&self, module: &[String], path: &[String]) -> Option<String> {
+    self
+      .resolve_segments(module, path, &mut Resolutions::new())
+      .map(|segments| segments.join("::"))
+  }
+
+  fn resolve_segments(
+    &self,
+    module: &[String],
+    path: &[String],
+    resolutions: &mut Resolutions,
+  ) -> Option<Vec<String>> {
+    let (first, rest) = path.split_first()?;
+
+    let mut current = match first.as_str() {
+      "crate" => vec![String::from("crate")],
+      "self" => module.to_vec(),
+      "super" => parent(module)?,
+      name => self.resolve_name(module, name, resolutions)?,
+    };
+
+    for segment in rest.iter() {
+      current = match segment.as_str() {
+        "super" => parent(&current)?,
+        name => self.resolve_name(&current, name, resolutions)?,
+      };
+    }
+
+    Some(current)
+  }
+
+  /// Resolves a single `name` that is in scope in `module`.
+  fn resolve_name(
+    &self,
+    module: &[String],
+    name: &str,
+    resolutions: &mut Resolutions,
+  ) -> Option<Vec<String>> {
+    let key = (module.join("::"), name.to_owned());
+
+    if let Some(resolution) = resolutions.get(&key) {
+      return resolution.clone();
+    }
+
+    resolutions.insert(key.clone(), None);
+
+    let resolution = self.resolve_name_in_scope(module, name, resolutions);
+    resolutions.insert(key, resolution.clone());
+
+    resolution
+  }
+
+  fn resolve_name_in_scope(
+    &self,
+    module: &[String],
+    name: &str,
+    resolutions: &mut Resolutions,
+  ) -> Option<Vec<String>> {
+    let scope = self.scopes.get(&module.join("::"))?;
+
+    if scope.items.contains(name) {
+      let mut path = module.to_vec();
+      path.push(name.to_owned());
+      return Some(path);
+    }
+
+    if let Some(import) = scope.imports.get(name) {
+      return self.resolve_segments(module, import, resolutions);
+    }
+
+    scope.glob_imports.iter().find_map(|glob_import| {
+      let glob_module = self.resolve_segments(module, glob_import, resolutions)?;
+      self.resolve_name(&glob_module, name, resolutions)
+    })
+  }
+}
+
+fn parent(module: &[String]) -> Option<Vec<String>> {
+  match module.split_last() {
+    Some((_, parent)) if !parent.is_empty() => Some(parent.to_vec()),
+    _ => None,
+  }
+}
+
+/// use a::{b, c as d, e::*}; -> imports: [(b, [a, b]), (d, [a, c])], glob_imports: [[a, e]]
+fn flatten_use_tree(
+  prefix: Vec<String>,
+  tree: &UseTree,
+  imports: &mut Vec<(String, Vec<String>)>,
+  glob_imports: &mut Vec<Vec<String>>,
+) {
+  match tree {
+    UseTree::Path(path) => {
+      let mut prefix = prefix;
+      prefix.push(path.ident.to_string());
+      flatten_use_tree(prefix, &path.tree, imports, glob_imports);
+    }
+    UseTree::Name(name) => {
+      // use a::{self}; imports a.
+      if name.ident == "self" {
+        if let Some(last) = prefix.last().cloned() {
+          imports.push((last, prefix));
+        }
+      } else {
+        let mut path = prefix;
+        path.push(name.ident.to_string());
+        imports.push((name.ident.to_string(), path));
+      }
+    }
+    UseTree::Rename(rename) => {
+      let mut path = prefix;
+      if rename.ident != "self" {
+        path.push(rename.ident.to_string());
+      }
+      // use a as _; only brings trait methods into scope.
+      if rename.rename != "_" {
+        imports.push((rename.rename.to_string(), path));
+      }
+    }
+    UseTree::Glob(_) => glob_imports.push(prefix),
+    UseTree::Group(group) => {
+      for tree in group.items.iter() {
+        flatten_use_tree(prefix.clone(), tree, imports, glob_imports);
+      }
+    }
+  }
+}
@@ -0,0 +1,335 @@
+use syn::{Attribute, Lit, Meta, NestedMeta};
+
+/// How a missing field gets its value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefaultValue {
+  /// #[serde(default)]
+  Trait,
+  /// #[serde(default = "default_limit")]
+  Function(String),
+}
+
+/// Returns the `default` of #[serde(default)] or #[serde(default = "path")].
+pub fn default_attribute(attrs: &[Attribute]) -> Option<DefaultValue> {
+  serde_meta(attrs).find_map(|meta| match meta {
+    Meta::Path(path) if path.is_ident("default") => Some(DefaultValue::Trait),
+    Meta::NameValue(name_value) if name_value.path.is_ident("default") => match name_value.lit {
+      Lit::Str(path) => Some(DefaultValue::Function(path.value())),
+      _ => None,
+    },
+    _ => None,
+  })
+}
+
+/// Case conventions of #[serde(rename_all = "...")].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameRule {
+  Lower,
+  Upper,
+  Pascal,
+  Camel,
+  Snake,
+  ScreamingSnake,
+  Kebab,
+  ScreamingKebab,
+}
+
+impl RenameRule {
+  fn from_str(rule: &str) -> Option<Self> {
+    match rule {
+      "lowercase" => Some(Self::Lower),
+      "UPPERCASE" => Some(Self::Upper),
+      "PascalCase" => Some(Self::Pascal),
+      "camelCase" => Some(Self::Camel),
+      "snake_case" => Some(Self::Snake),
+      "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnake),
+      "kebab-case" => Some(Self::Kebab),
+      "SCREAMING-KEBAB-CASE" => Some(Self::ScreamingKebab),
+      _ => None,
+    }
+  }
+
+  /// Renames a PascalCase variant: VeryTasty -> very_tasty
+  pub fn apply_to_variant(self, variant: &str) -> String {
+    match self {
+      Self::Pascal => variant.to_owned(),
+      Self::Lower => variant.to_ascii_lowercase(),
+      Self::Upper => variant.to_ascii_uppercase(),
+      Self::Camel => {
+        let mut chars = variant.chars();
+        match chars.next() {
+          None => String::new(),
+          Some(first) => first.to_ascii_lowercase().to_string() + chars.as_str(),
+        }
+      }
+      Self::Snake => {
+        let mut snake = String::new();
+        for (i, c) in variant.char_indices() {
+          if i > 0 && c.is_uppercase() {
+            snake.push('_');
+          }
+          snake.push(c.to_ascii_lowercase());
+        }
+        snake
+      }
+      Self::ScreamingSnake => Self::Snake.apply_to_variant(variant).to_ascii_uppercase(),
+      Self::Kebab => Self::Snake.apply_to_variant(variant).replace('_', "-"),
+      Self::ScreamingKebab => Self::ScreamingSnake
+        .apply_to_variant(variant)
+        .replace('_', "-"),
+    }
+  }
+
+  /// Renames a snake_case field: very_tasty -> veryTasty
+  pub fn apply_to_field(self, field: &str) -> String {
+    match self {
+      Self::Lower | Self::Snake => field.to_owned(),
+      Self::Upper | Self::ScreamingSnake => field.to_ascii_uppercase(),
+      Self::Pascal => {
+        let mut pascal = String::new();
+        let mut capitalize = true;
+        for c in field.chars() {
+          if c == '_' {
+            capitalize = true;
+          } else if capitalize {
+            pascal.push(c.to_ascii_uppercase());
+            capitalize = false;
+          } else {
+            pascal.push(c);
+          }
+        }
+        pascal
+      }
+      Self::Camel => {
+        let pascal = Self::Pascal.apply_to_field(field);
+        Self::Camel.apply_to_variant(&pascal)
+      }
+      Self::Kebab => field.replace('_', "-"),
+      Self::ScreamingKebab => field.to_ascii_uppercase().replace('_', "-"),
+    }
+  }
+}
+
+/// Rules of #[serde(rename_all = "...")] or #[serde(rename_all(serialize = "...", deserialize = "..."))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenameAll {
+  pub serialize: Option<RenameRule>,
+  pub deserialize: Option<RenameRule>,
+}
+
+/// Returns the rename_all rules of a container or enum variant.
+pub fn rename_all(attrs: &[Attribute]) -> RenameAll {
+  let (serialize, deserialize) = serialize_deserialize_values(attrs, "rename_all");
+
+  let rule = |value: Option<String>| {
+    let value = value?;
+    let rule = RenameRule::from_str(&value);
+    if rule.is_none() {
+      tracing::warn!("unknown #[serde(rename_all = \"{}\")] rule", value);
+    }
+    rule
+  };
+
+  RenameAll {
+    serialize: rule(serialize),
+    deserialize: rule(deserialize),
+  }
+}
+
+/// Name of a field or variant in serialized and deserialized values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Name {
+  pub serialize: String,
+  pub deserialize: String,
+}
+
+/// Returns the name of the variant `ident`, renamed by #[serde(rename)] or the rename_all of its enum.
+pub fn variant_name(attrs: &[Attribute], ident: &str, rename_all: RenameAll) -> Name {
+  name(attrs, ident, rename_all, RenameRule::apply_to_variant)
+}
+
+/// The #[serde(...)] attributes of a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldAttrs {
+  pub name: Name,
+  /// #[serde(alias = "...")], names also accepted when deserializing.
+  pub aliases: Vec<String>,
+  /// #[serde(skip)] or #[serde(skip_serializing)]
+  pub skip_serializing: bool,
+  /// #[serde(skip)] or #[serde(skip_deserializing)]
+  pub skip_deserializing: bool,
+  /// #[serde(skip_serializing_if = "...")], the field may be missing from serialized values.
+  pub skip_serializing_if: bool,
+  /// #[serde(flatten)], the fields of the value are next to the other fields.
+  pub flatten: bool,
+}
+
+/// Returns the serde attributes of the field `ident`, `rename_all` is the rule of its container.
+pub fn field_attrs(attrs: &[Attribute], ident: &str, rename_all: RenameAll) -> FieldAttrs {
+  let mut field_attrs = FieldAttrs {
+    name: name(attrs, ident, rename_all, RenameRule::apply_to_field),
+    aliases: vec![],
+    skip_serializing: false,
+    skip_deserializing: false,
+    skip_serializing_if: false,
+    flatten: false,
+  };
+
+  for meta in serde_meta(attrs) {
+    match &meta {
+      Meta::Path(path) if path.is_ident("skip") => {
+        field_attrs.skip_serializing = true;
+        field_attrs.skip_deserializing = true;
+      }
+      Meta::Path(path) if path.is_ident("skip_serializing") => field_attrs.skip_serializing = true,
+      Meta::Path(path) if path.is_ident("skip_deserializing") => {
+        field_attrs.skip_deserializing = true
+      }
+      Meta::Path(path) if path.is_ident("flatten") => field_attrs.flatten = true,
+      Meta::NameValue(name_value) if name_value.path.is_ident("skip_serializing_if") => {
+        field_attrs.skip_serializing_if = true
+      }
+      Meta::NameValue(name_value) if name_value.path.is_ident("alias") => {
+        if let Lit::Str(alias) = &name_value.lit {
+          field_attrs.aliases.push(alias.value());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  field_attrs
+}
+
+/// Returns true for variants annotated with #[serde(skip)]
+pub fn is_skipped(attrs: &[Attribute]) -> bool {
+  has_flag(attrs, "skip")
+}
+
+/// Returns true for structs annotated with #[serde(transparent)]
+pub fn is_transparent(attrs: &[Attribute]) -> bool {
+  has_flag(attrs, "transparent")
+}
+
+/// Returns true for containers annotated with #[serde(deny_unknown_fields)]
+pub fn deny_unknown_fields(attrs: &[Attribute]) -> bool {
+  has_flag(attrs, "deny_unknown_fields")
+}
+
+/// Name of a field or variant: #[serde(rename)] wins over the rename_all rule.
+fn name(
+  attrs: &[Attribute],
+  ident: &str,
+  rename_all: RenameAll,
+  apply: fn(RenameRule, &str) -> String,
+) -> Name {
+  let (serialize, deserialize) = serialize_deserialize_values(attrs, "rename");
+
+  let rename = |value: Option<String>, rule: Option<RenameRule>| {
+    value.unwrap_or_else(|| match rule {
+      None => ident.to_owned(),
+      Some(rule) => apply(rule, ident),
+    })
+  };
+
+  Name {
+    serialize: rename(serialize, rename_all.serialize),
+    deserialize: rename(deserialize, rename_all.deserialize),
+  }
+}
+
+/// Values of #[serde(name = "...")] or #[serde(name(serialize = "...", deserialize = "..."))]
+fn serialize_deserialize_values(
+  attrs: &[Attribute],
+  name: &str,
+) -> (Option<String>, Option<String>) {
+  let mut serialize = None;
+  let mut deserialize = None;
+
+  for meta in serde_meta(attrs) {
+    match meta {
+      Meta::NameValue(name_value) if name_value.path.is_ident(name) => {
+        if let Lit::Str(value) = name_value.lit {
+          serialize = Some(value.value());
+          deserialize = Some(value.value());
+        }
+      }
+      Meta::List(list) if list.path.is_ident(name) => {
+        for nested in list.nested {
+          if let NestedMeta::Meta(Meta::NameValue(name_value)) = nested {
+            match (name_value.path.get_ident(), name_value.lit) {
+              (Some(ident), Lit::Str(value)) if ident == "serialize" => {
+                serialize = Some(value.value())
+              }
+              (Some(ident), Lit::Str(value)) if ident == "deserialize" => {
+                deserialize = Some(value.value())
+              }
+              _ => {}
+            }
+          }
+        }
+      }
+      _ => {}
+    }
+  }
+
+  (serialize, deserialize)
+}
+
+/// Returns true when a #[serde(...)] attribute contains the word `name`.
+fn has_flag(attrs: &[Attribute], name: &str) -> bool {
+  serde_meta(attrs).any(|meta| matches!(meta, Meta::Path(path) if path.is_ident(name)))
+}
+
+/// Items of every #[serde(...)] attribute.
+fn serde_meta(attrs: &[Attribute]) -> impl Iterator<Item = Meta> + '_ {
+  attrs
+    .iter()
+    .filter(|attr| attr.path.is_ident("serde"))
+    .filter_map(|attr| match attr.parse_meta() {
+      Ok(Meta::List(list)) => Some(list.nested.into_iter()),
+      _ => None,
+    })
+    .flatten()
+    .filter_map(|nested| match nested {
+      NestedMeta::Meta(meta) => Some(meta),
+      NestedMeta::Lit(_) => None,
+    })
+}
+
+/// How the variants of an enum are serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnumRepresentation {
+  /// {"Variant": {...}}, the default.
+  External,
+  /// #[serde(tag = "type")]: {"type": "Variant", ...}
+  Internal { tag: String },
+  /// #[serde(tag = "t", content = "c")]: {"t": "Variant", "c": {...}}
+  Adjacent { tag: String, content: String },
+  /// #[serde(untagged)]: {...}
+  Untagged,
+}
+
+/// Returns the representation chosen with #[serde(tag)], #[serde(content)] or #[serde(untagged)].
+pub fn enum_representation(attrs: &[Attribute]) -> EnumRepresentation {
+  let mut tag = None;
+  let mut content = None;
+
+  for meta in serde_meta(attrs) {
+    match meta {
+      Meta::Path(path) if path.is_ident("untagged") => return EnumRepresentation::Untagged,
+      Meta::NameValue(name_value) => match (name_value.path.get_ident(), name_value.lit) {
+        (Some(ident), Lit::Str(value)) if ident == "tag" => tag = Some(value.value()),
+        (Some(ident), Lit::Str(value)) if ident == "content" => content = Some(value.value()),
+        _ => {}
+      },
+      _ => {}
+    }
+  }
+
+  match (tag, content) {
+    (Some(tag), Some(content)) => EnumRepresentation::Adjacent { tag, content },
+    (Some(tag), None) => EnumRepresentation::Internal { tag },
+    _ => EnumRepresentation::External,
+  }
+}
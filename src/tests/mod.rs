@@ -4,7 +4,7 @@ use semaphore::Semaphore;
 
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter, Registry};
 
-use crate::run;
+use crate::{cli::Args, run};
 
 mod semaphore;
 
@@ -18,40 +18,41 @@ fn runner() -> Result<(), Box<dyn std::error::Error>> {
 
   let mut handles = vec![];
 
+  // A test case is either a `.input` file or a directory containing a crate,
+  // the expected document is in a `.json` file with the same name.
   for file_path in std::fs::read_dir("src/tests/test_cases")? {
     let file_path = file_path.unwrap();
 
-    if file_path.file_type()?.is_dir() {
-      panic!("test cases folder should contain only files that aren't directories");
-    }
-
     let path = file_path.path().to_str().map(|s| s.to_owned()).unwrap();
-    if !path.ends_with(".input") {
+    if !file_path.file_type()?.is_dir() && !path.ends_with(".input") {
       continue;
     }
 
     let sema_clone = Arc::clone(&sema);
 
-    if !path.ends_with("basic_1.input") {
-      continue;
-    }
-
     // TODO: this is bad since many threads could crash the system,
     // we should acquire the semaphore before spawning the thread.
     handles.push(std::thread::spawn(move || {
       let _guard = sema_clone.acquire(1);
 
-      let actual = run(&path).unwrap();
+      let documents = run(&Args::new(vec![path.clone().into()])).unwrap();
 
-      let output_file_path = path.replace(".input", ".json");
+      // Documents generated for Cargo targets are compared with `<test case>.<target>.json`.
+      for document in documents {
+        let output_file_path = match &document.name {
+          None => std::path::Path::new(&path).with_extension("json"),
+          Some(name) => std::path::PathBuf::from(format!("{}.{}.json", path, name)),
+        };
 
-      let expected = std::fs::read_to_string(&output_file_path).unwrap();
+        let expected = std::fs::read_to_string(&output_file_path).unwrap();
+        let actual = document.contents;
 
-      assert_eq!(
-        expected, actual,
-        "\nexpected: {} \ngot: {}",
-        expected, actual
-      );
+        assert_eq!(
+          expected, actual,
+          "\nexpected: {} \ngot: {}",
+          expected, actual
+        );
+      }
     }));
   }
 
@@ -0,0 +1,15 @@
+mod models;
+mod prelude;
+
+use models::*;
+use prelude::*;
+
+fn main() {
+  let app = Router::new()
+    .route("/orders", post(create_order))
+    .route("/settings", get(settings));
+}
+
+async fn create_order(Json(body): Json<CreateOrder>) -> Json<Order> {}
+
+async fn settings() -> Json<Settings> {}
@@ -0,0 +1,6 @@
+use super::*;
+
+pub struct Item {
+  pub sku: String,
+  pub quantity: u32,
+}
@@ -0,0 +1,7 @@
+mod item;
+mod order;
+mod user;
+
+pub use item::*;
+pub use order::*;
+pub use user::*;
@@ -0,0 +1,10 @@
+use super::*;
+
+pub struct Order {
+  pub customer: User,
+  pub items: Vec<Item>,
+}
+
+pub struct CreateOrder {
+  pub items: Vec<Item>,
+}
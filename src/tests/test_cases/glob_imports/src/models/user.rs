@@ -0,0 +1,5 @@
+use super::*;
+
+pub struct User {
+  pub name: String,
+}
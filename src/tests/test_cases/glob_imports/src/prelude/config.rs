@@ -0,0 +1,6 @@
+use crate::prelude::*;
+
+pub struct Settings {
+  pub theme: Theme,
+  pub flags: Vec<Flag>,
+}
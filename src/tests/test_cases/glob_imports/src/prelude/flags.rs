@@ -0,0 +1,6 @@
+use crate::prelude::*;
+
+pub struct Flag {
+  pub name: String,
+  pub enabled: bool,
+}
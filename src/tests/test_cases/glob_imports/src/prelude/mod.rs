@@ -0,0 +1,7 @@
+mod config;
+mod flags;
+mod theme;
+
+pub use config::*;
+pub use flags::*;
+pub use theme::*;
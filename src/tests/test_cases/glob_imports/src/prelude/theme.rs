@@ -0,0 +1,6 @@
+use crate::prelude::*;
+
+pub enum Theme {
+  Light,
+  Dark,
+}
@@ -0,0 +1,4 @@
+pub struct CreateUser {
+  pub name: String,
+  pub address: crate::models::Address,
+}
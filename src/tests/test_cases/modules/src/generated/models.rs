@@ -0,0 +1,3 @@
+pub struct Address {
+  pub street: String,
+}
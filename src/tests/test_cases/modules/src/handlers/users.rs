@@ -0,0 +1,3 @@
+use crate::dto::CreateUser;
+
+pub async fn create(Json(body): Json<CreateUser>) {}
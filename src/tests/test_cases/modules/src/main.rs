@@ -0,0 +1,16 @@
+mod dto;
+mod routes;
+
+mod handlers {
+  pub mod users;
+}
+
+#[path = "generated/models.rs"]
+mod models;
+
+#[cfg(test)]
+mod tests;
+
+fn main() {
+  let app = routes::router();
+}
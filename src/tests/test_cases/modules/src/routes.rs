@@ -0,0 +1,7 @@
+mod health;
+
+pub fn router() -> Router {
+  let router = Router::new().route("/users", post(crate::handlers::users::create));
+
+  router
+}
@@ -0,0 +1,7 @@
+pub fn router() -> Router {
+  let router = Router::new().route("/health", get(health));
+
+  router
+}
+
+async fn health() {}
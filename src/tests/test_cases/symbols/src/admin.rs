@@ -0,0 +1,4 @@
+pub struct CreateRequest {
+  pub name: String,
+  pub level: i32,
+}
@@ -0,0 +1,8 @@
+use super::super::users;
+use crate::admin::CreateRequest as CreateAdminRequest;
+
+pub async fn create_user(Json(body): Json<users::CreateRequest>) {}
+
+pub async fn create_admin(Json(body): Json<CreateAdminRequest>) {}
+
+pub async fn health() {}
@@ -0,0 +1,4 @@
+pub mod handlers;
+mod settings;
+
+pub use self::settings::Settings;
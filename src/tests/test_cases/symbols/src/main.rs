@@ -0,0 +1,21 @@
+mod admin;
+mod api;
+mod users;
+
+use api::handlers as h;
+use api::handlers::*;
+
+pub struct RequestBody {
+  pub user: users::CreateRequest,
+  pub admin: self::admin::CreateRequest,
+  pub settings: api::Settings,
+}
+
+fn main() {
+  let users = Router::new().route("/users", post(h::create_user));
+  let admins = Router::new().route("/admins", post(crate::api::handlers::create_admin));
+  let health = Router::new().route("/health", get(health));
+  let bulk = Router::new().route("/bulk", post(create_bulk));
+}
+
+async fn create_bulk(Json(body): Json<RequestBody>) {}
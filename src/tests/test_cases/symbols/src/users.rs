@@ -0,0 +1,3 @@
+pub struct CreateRequest {
+  pub name: String,
+}
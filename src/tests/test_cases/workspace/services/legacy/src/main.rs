@@ -0,0 +1,5 @@
+fn main() {
+  let app = Router::new().route("/legacy", get(legacy));
+}
+
+async fn legacy() {}
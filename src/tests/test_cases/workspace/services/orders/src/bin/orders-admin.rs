@@ -0,0 +1,5 @@
+fn main() {
+  let app = Router::new().route("/admin/orders", get(list_orders));
+}
+
+async fn list_orders() {}
@@ -0,0 +1,10 @@
+fn main() {
+  let app = Router::new().route("/orders", post(create_order));
+}
+
+struct RequestBody {
+  pub product_id: i64,
+  pub quantity: i32,
+}
+
+async fn create_order(Json(body): Json<RequestBody>) {}
@@ -0,0 +1,9 @@
+fn main() {
+  let app = Router::new().route("/users", post(create_user));
+}
+
+struct RequestBody {
+  pub name: String,
+}
+
+async fn create_user(Json(body): Json<RequestBody>) {}